@@ -0,0 +1,34 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Commands seen by the shell hook, per user in the state dir.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CaptureStore {
+    /// Last command run outside sebas, offered by `sebas add`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last: Option<String>,
+    #[serde(default)]
+    pub(crate) commands: BTreeMap<String, CaptureEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CaptureEntry {
+    /// Runs within the configured window, failed ones left out when `success_only` is set
+    #[serde(default)]
+    pub(crate) runs: Vec<String>,
+    /// When the user last said no, the command is not suggested again inside the window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) declined_at: Option<String>,
+    /// Never suggest this command
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) ignored: bool,
+}
@@ -0,0 +1,166 @@
+use std::{fs, io::{self, Write}, path::PathBuf};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{commands::{capture::definition::{CaptureEntry, CaptureStore, HookShell}, core::definition::{CaptureConfig, FolderConfig}}, utils::{dir::{find_sebas_dir, get_all_sebas_dirs, get_state_dir, write_private}, secrets}, SebasApp};
+
+const BASH_HOOK: &str = r#"__sebas_capture() {
+    local exit_code=$? entry number command
+    entry=$(HISTTIMEFORMAT= builtin history 1)
+    entry=${entry#"${entry%%[![:space:]]*}"}
+    number=${entry%%[[:space:]]*}
+    command=${entry#"$number"}
+    command=${command#"${command%%[![:space:]]*}"}
+    # The first prompt shows the last command of an earlier session. With
+    # HISTCONTROL=ignoredups a repeat keeps the number, so it is not seen again
+    if [[ -n $__sebas_seen && -n $number && $number != "$__sebas_seen" ]]; then
+        sebas capture --status "$exit_code" -- "$command"
+    fi
+    __sebas_seen=${number:-0}
+    return $exit_code
+}
+if [[ ";${PROMPT_COMMAND[*]:-};" != *";__sebas_capture;"* ]]; then
+    PROMPT_COMMAND="__sebas_capture${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+"#;
+
+const ZSH_HOOK: &str = r#"__sebas_preexec() {
+    __sebas_command=$1
+}
+__sebas_precmd() {
+    local exit_code=$?
+    if [[ -n $__sebas_command ]]; then
+        sebas capture --status "$exit_code" -- "$__sebas_command"
+        __sebas_command=
+    fi
+}
+autoload -Uz add-zsh-hook
+add-zsh-hook preexec __sebas_preexec
+add-zsh-hook precmd __sebas_precmd
+"#;
+
+const FISH_HOOK: &str = r#"function __sebas_capture --on-event fish_postexec
+    set -l exit_code $status
+    if test -n "$argv[1]"
+        sebas capture --status $exit_code -- $argv[1]
+    end
+end
+"#;
+
+fn is_within(timestamp: &str, cutoff: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(timestamp).is_ok_and(|at| at.with_timezone(&Utc) >= cutoff)
+}
+
+impl CaptureStore {
+    pub fn path() -> Option<PathBuf> {
+        get_state_dir().map(|dir| dir.join("captures.yaml"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not determine the sebas state directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    // Forget runs and refusals older than the window so the file stays small
+    fn prune(&mut self, cutoff: DateTime<Utc>) {
+        for entry in self.commands.values_mut() {
+            entry.runs.retain(|run| is_within(run, cutoff));
+            if entry.declined_at.as_deref().is_some_and(|at| !is_within(at, cutoff)) {
+                entry.declined_at = None;
+            }
+        }
+        self.commands.retain(|_, entry| !entry.runs.is_empty() || entry.declined_at.is_some() || entry.ignored);
+    }
+}
+
+impl CaptureEntry {
+    fn should_suggest(&self, command: &str, succeeded: bool, config: &CaptureConfig) -> bool {
+        if self.ignored || self.declined_at.is_some() || (config.success_only && !succeeded) {
+            return false;
+        }
+        let long = config.min_length > 0 && command.chars().count() >= config.min_length;
+        let repeated = config.repeat > 0 && self.runs.len() >= config.repeat;
+        long || repeated
+    }
+}
+
+impl SebasApp {
+    pub fn print_shell_hook(shell: HookShell) {
+        let script = match shell {
+            HookShell::Bash => BASH_HOOK,
+            HookShell::Zsh => ZSH_HOOK,
+            HookShell::Fish => FISH_HOOK,
+        };
+        print!("{}", script);
+    }
+
+    fn is_bookmarked(command: &str) -> bool {
+        let hash = Self::generate_hash(command);
+        get_all_sebas_dirs().into_iter().any(|sebas_dir| {
+            let app = SebasApp { sebas_dir };
+            app.get_all_groups()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|group| app.load_group(group).ok())
+                .any(|group| group.commands.iter().any(|cmd| cmd.hash == hash))
+        })
+    }
+
+    /// Called by the shell hook after every command, offers to bookmark it once
+    /// it is long enough or repeated often enough.
+    pub fn capture_command(command: &str, status: i32) -> Result<(), Box<dyn std::error::Error>> {
+        let command = command.trim();
+        if command.is_empty() || command == "sebas" || command.starts_with("sebas ") {
+            return Ok(());
+        }
+        // The store is plain text, a token typed on the command line must not end up there
+        if !secrets::scan(command).is_empty() {
+            return Ok(());
+        }
+
+        let sebas_dir = find_sebas_dir();
+        let config = sebas_dir.as_deref().map(FolderConfig::load).unwrap_or_default().capture;
+        let now = Utc::now();
+        let succeeded = status == 0;
+
+        let mut store = CaptureStore::load();
+        store.last = Some(command.to_string());
+        store.prune(now - Duration::days(config.days));
+        if succeeded || !config.success_only {
+            store.commands.entry(command.to_string()).or_default().runs.push(now.to_rfc3339());
+        }
+        let suggest = store.commands.get(command).is_some_and(|entry| entry.should_suggest(command, succeeded, &config));
+        store.save()?;
+
+        let Some(sebas_dir) = sebas_dir else {
+            return Ok(());
+        };
+        if !suggest || !atty::is(atty::Stream::Stdin) || !atty::is(atty::Stream::Stdout) || Self::is_bookmarked(command) {
+            return Ok(());
+        }
+
+        print!("sebas: bookmark '{}'? [y]es, [n]ot now, ne[v]er (y/N/v) ", command);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+
+        let mut store = CaptureStore::load();
+        let entry = store.commands.entry(command.to_string()).or_default();
+        match input.trim().to_lowercase().as_str() {
+            "y" | "yes" => {
+                return SebasApp { sebas_dir }.add_command(Some(command.to_string()), None, None, None, false, false);
+            }
+            "v" | "never" => entry.ignored = true,
+            _ => entry.declined_at = Some(now.to_rfc3339()),
+        }
+        store.save()
+    }
+}
@@ -1,8 +1,9 @@
 use std::path::PathBuf;
 
 use clap::Subcommand;
+use clap_complete::{engine::ArgValueCandidates, Shell};
 
-use crate::commands::group::definition::GroupAction;
+use crate::{commands::{capture::definition::HookShell, completions::{complete_groups, complete_identifiers}, export::definition::ExportFormat, group::definition::GroupAction, import::definition::{ImportAction, ImportSource}, pack::definition::PackAction, scope::definition::ScopeArgs, targets::definition::TargetArgs, trash::definition::TrashAction, usage::definition::SortOrder}, utils::output::OutputFormat};
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -12,11 +13,19 @@ pub enum Commands {
         /// Command to add (optional if using stdin)
         command: Option<String>,
         /// Group name (defaults to "Miscellaneous")
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(complete_groups))]
         group: Option<String>,
         /// Comment for the command
         #[arg(short, long)]
         comment: Option<String>,
+        /// Short name for the command
+        #[arg(short, long)]
+        name: Option<String>,
+        /// Always ask for confirmation before using this command
+        #[arg(long)]
+        danger: bool,
+        #[command(flatten)]
+        scope: ScopeArgs,
         /// Skip confirmation prompts
         #[arg(short, long)]
         yes: bool,
@@ -25,7 +34,7 @@ pub enum Commands {
     #[command(alias = "ls")]
     List {
         /// Filter by group
-        #[arg(short, long)]
+        #[arg(short, long, add = ArgValueCandidates::new(complete_groups))]
         group: Option<String>,
         /// Show verbose output with full paths
         #[arg(short, long)]
@@ -33,21 +42,52 @@ pub enum Commands {
         /// Plain output for scripting
         #[arg(short, long)]
         plain: bool,
+        /// Sort order of the listed commands
+        #[arg(short, long, value_enum, default_value_t = SortOrder::Frecency)]
+        sort: SortOrder,
+        /// Machine-readable output with every field
+        #[arg(short, long, value_enum, conflicts_with_all = ["verbose", "plain"])]
+        format: Option<OutputFormat>,
+    },
+    /// Show every field of a saved command
+    Show {
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
+        identifier: String,
+        /// Machine-readable output
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
     },
     /// Edit a saved command
     #[command(alias = "e")]
     Edit {
-        /// Command index or hash
-        identifier: String,
+        /// Command index, name or hash
+        #[arg(required_unless_present = "group", add = ArgValueCandidates::new(complete_identifiers))]
+        identifier: Option<String>,
         /// New command text
         #[arg(long)]
         new_command: Option<String>,
         /// New group name
-        #[arg(long)]
+        #[arg(long, add = ArgValueCandidates::new(complete_groups))]
         new_group: Option<String>,
         /// New comment
         #[arg(long)]
         new_comment: Option<String>,
+        /// New name
+        #[arg(long)]
+        new_name: Option<String>,
+        /// Mark or unmark the command as dangerous
+        #[arg(long)]
+        danger: Option<bool>,
+        /// Open the command in $EDITOR
+        #[arg(short, long)]
+        interactive: bool,
+        /// Edit every command of a group of the nearest folder in $EDITOR
+        #[arg(short, long, requires = "interactive", conflicts_with = "identifier", add = ArgValueCandidates::new(complete_groups))]
+        group: Option<String>,
+        /// Move the command to another folder
+        #[command(flatten)]
+        scope: ScopeArgs,
         /// Skip confirmation prompts
         #[arg(short, long)]
         yes: bool,
@@ -55,7 +95,8 @@ pub enum Commands {
     /// Remove a saved command
     #[command(alias = "rm")]
     Remove {
-        /// Command index or hash
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
         identifier: String,
         /// Skip confirmation prompts
         #[arg(short, long)]
@@ -70,23 +111,209 @@ pub enum Commands {
     /// Obtain command
     #[command(alias = "o")]
     Obtain {
-        /// Command index or hash
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
+        identifier: Option<String>,
+        /// Hide commands that do not apply to the current directory
+        #[arg(short, long)]
+        relevant: bool,
+        /// Copy the command to the clipboard instead of typing it
+        #[arg(short, long)]
+        copy: bool,
+    },
+    /// Print the chosen command with its placeholders filled in, for $(sebas pick) and key bindings
+    #[command(alias = "p")]
+    Pick {
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
+        identifier: Option<String>,
+        /// Hide commands that do not apply to the current directory
+        #[arg(short, long)]
+        relevant: bool,
+    },
+    /// Run a command directly
+    #[command(alias = "r")]
+    Run {
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
         identifier: Option<String>,
+        /// Hide commands that do not apply to the current directory
+        #[arg(short, long)]
+        relevant: bool,
+        /// Record the output, exit status and duration, see 'sebas runs'
+        #[arg(long, conflicts_with_all = ["pattern", "targets"])]
+        capture: bool,
+        #[command(flatten)]
+        targets: TargetArgs,
     },
+    /// Show captured runs of a command
+    Runs {
+        /// Command index, name or hash
+        #[arg(add = ArgValueCandidates::new(complete_identifiers))]
+        identifier: String,
+        /// Show the output of one run, 1 being the latest
+        #[arg(short, long)]
+        run: Option<usize>,
+        /// Machine-readable output
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
+    /// Unlock encrypted groups for a while
+    Unlock {
+        /// Key file mixed into the passphrase
+        #[arg(short, long)]
+        key_file: Option<PathBuf>,
+        /// Minutes until the groups lock again
+        #[arg(short, long, default_value_t = 15)]
+        timeout: i64,
+    },
+    /// Lock encrypted groups again
+    Lock,
     /// Group management
     #[command(alias = "g")]
     Group {
         #[command(subcommand)]
         action: GroupAction,
     },
+    /// Import commands from other tools
+    #[command(args_conflicts_with_subcommands = true)]
+    Import {
+        #[command(subcommand)]
+        action: Option<ImportAction>,
+        /// Snippet manager to import from
+        #[arg(short, long, value_enum, requires = "path")]
+        from: Option<ImportSource>,
+        /// Cheatsheet file or directory
+        path: Option<PathBuf>,
+        /// Group for untagged snippets (defaults to the file name)
+        #[arg(short, long, add = ArgValueCandidates::new(complete_groups))]
+        group: Option<String>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Build, verify and install signed command packs
+    Pack {
+        #[command(subcommand)]
+        action: PackAction,
+    },
+    /// Export commands as a cheatsheet, shell aliases or data
+    Export {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+        /// Filter by group
+        #[arg(short, long, add = ArgValueCandidates::new(complete_groups))]
+        group: Option<String>,
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
     /// Initialize a .sebas folder
     Init {
         /// Path to initialize (defaults to current directory)
         path: Option<PathBuf>,
+        /// Record every change of the folder in a local git repository
+        #[arg(long)]
+        git: bool,
     },
     /// Sync commands from nested .sebas folders
     Sync,
-
+    /// Show most, least and never used commands
+    Stats {
+        /// Number of commands shown in the most and least used lists
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show the change history of a versioned .sebas folder
+    Log {
+        /// .sebas folder (defaults to the nearest one)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// Number of changes shown
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Show a recorded change, or uncommitted edits when no revision is given
+    Diff {
+        /// Revision from 'sebas log'
+        rev: Option<String>,
+        /// .sebas folder (defaults to the nearest one)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
+    /// Undo a recorded change
+    Revert {
+        /// Revision from 'sebas log'
+        rev: String,
+        /// .sebas folder (defaults to the nearest one)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Undo the last add, edit, remove or move
+    Undo {
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Manage removed commands and groups
+    Trash {
+        #[command(subcommand)]
+        action: TrashAction,
+    },
+    /// Review a .sebas folder and allow running its commands
+    Trust {
+        /// Project or .sebas folder (defaults to the nearest one)
+        path: Option<PathBuf>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Stop trusting a .sebas folder
+    Untrust {
+        /// Project or .sebas folder (defaults to the nearest one)
+        path: Option<PathBuf>,
+    },
+    /// Print a hook that suggests bookmarking long or repeated commands
+    ///
+    /// Commands that look like they contain a secret are not recorded. In bash with
+    /// HISTCONTROL=ignoredups or ignoreboth, running the previous command again adds no
+    /// history entry, so back-to-back repeats count as one run.
+    ShellHook {
+        /// Shell to hook into
+        shell: HookShell,
+    },
+    /// Record a command run in the shell, used by the shell hook
+    #[command(hide = true)]
+    Capture {
+        /// Exit status of the command
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        status: i32,
+        /// Command as typed
+        #[arg(last = true)]
+        command: String,
+    },
+    /// Print a shell completion script
+    Completions {
+        /// Shell to complete for
+        shell: Shell,
+        /// Only subcommands and flags, without group names and identifiers
+        #[arg(long = "static")]
+        static_only: bool,
+    },
 }
 
-
+/// Changes requested by `sebas edit`, `None` leaves a field untouched.
+#[derive(Debug, Default)]
+pub struct CommandEdit {
+    pub(crate) command: Option<String>,
+    pub(crate) group: Option<String>,
+    pub(crate) comment: Option<String>,
+    pub(crate) name: Option<String>,
+    pub(crate) danger: Option<bool>,
+    /// Folder to move the command to
+    pub(crate) folder: Option<PathBuf>,
+}
@@ -1,10 +1,15 @@
 use anyhow::Context;
+use colored::Colorize;
 
-use crate::{ commands::core::definition::SavedCommand, utils::{fzf::sebas_fzf_run, preseed::preseed}, SebasApp};
-use std::process::Command as ProcessCommand;
+use crate::{ commands::{commands::definition::CommandEdit, core::definition::{FolderConfig, ObtainAction, ResolvedCommand, SavedCommand}, group::definition::GroupSummary, targets::definition::TargetArgs, trash::definition::{TrashEntry, TrashItem}, usage::definition::{SortOrder, UsageStore}}, utils::{clipboard, context::{DirContext, Relevance}, danger::DangerChecker, dir::get_all_sebas_dirs, fzf::sebas_fzf_run, output::{render, OutputFormat}, preseed::preseed}, SebasApp};
+
+const COMMAND_COLUMNS: &[&str] = &["index", "name", "group", "hash", "command", "comment", "folder_path", "trusted", "danger", "created_at"];
+const GROUP_COLUMNS: &[&str] = &["name", "commands", "encrypted", "locked", "folder_path"];
+// Copies the selection in the picker instead of the default action
+const COPY_KEY: &str = "ctrl-y";
 
 impl SebasApp {
-    pub fn add_command(&self, command_text: Option<String>, group: Option<String>, comment: Option<String>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn add_command(&self, command_text: Option<String>, group: Option<String>, comment: Option<String>, name: Option<String>, danger: bool, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
         let command = if let Some(cmd) = command_text {
             cmd
         } else if let Some(cmd) = Self::get_command_from_stdin() {
@@ -16,44 +21,77 @@ impl SebasApp {
             }
             cmd
         } else {
-            return Err("No command provided. Use stdin, provide as argument, or ensure shell history is available.".into());
+            return Err("No command provided. Use stdin, provide as argument, or install the shell hook with 'sebas shell-hook'.".into());
         };
 
+        let command = Self::redact_secrets(command, yes)?;
+
         let group_name = group.unwrap_or_else(|| "Miscellaneous".to_string());
         let mut group_data = self.load_group(&group_name)?;
         
         // Check if group file exists
         let group_file = self.sebas_dir.join(format!("{}.yaml", group_name));
-        if !group_file.exists() && !yes {
-            if !Self::confirm(&format!("Group '{}' does not exist. Create it?", group_name)) {
-                println!("Command not added.");
-                return Ok(());
-            }
+        if !group_file.exists() && !group_file.with_extension("enc").exists() && !yes && !Self::confirm(&format!("Group '{}' does not exist. Create it?", group_name)) {
+            println!("Command not added.");
+            return Ok(());
         }
 
         let hash = Self::generate_hash(&command);
         let saved_command = SavedCommand {
             command: command.clone(),
+            name,
             comment,
             hash: hash.clone(),
             created_at: chrono::Utc::now().to_rfc3339(),
+            when: None,
+            vars: Default::default(),
+            cwd: None,
+            env: Default::default(),
+            variants: Default::default(),
+            danger,
         };
 
+        if !Self::confirm_dangerous_save(&saved_command, yes) {
+            println!("Command not added.");
+            return Ok(());
+        }
+
         group_data.commands.push(saved_command);
-        self.save_group(&group_name, &group_data)?;
+        Self::journaled(format!("add '{}'", command), self.group_files(&group_name), || self.save_group(&group_name, group_file, &group_data))?;
         
         println!("Command added to group '{}' with hash {}", group_name, hash);
         Ok(())
     }
 
-    pub fn list_commands(&self, group_filter: Option<String>, verbose: bool, plain: bool) -> Result<(), Box<dyn std::error::Error>> {
-        let resolved = self.resolve_all_commands();
+    fn variants_marker(command: &ResolvedCommand) -> String {
+        let variants = &command.command.variants;
+        if variants.is_empty() {
+            return String::new();
+        }
+        let keys: Vec<&str> = variants.keys().map(String::as_str).collect();
+        format!(" {}", format!("[variants: {}]", keys.join(", ")).dimmed())
+    }
+
+    pub fn list_commands(&self, group_filter: Option<String>, verbose: bool, plain: bool, sort: SortOrder, format: Option<OutputFormat>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut resolved = self.resolve_all_commands();
+
+        if let Some(format) = format {
+            if sort == SortOrder::Frecency {
+                UsageStore::load().sort_by_frecency(&mut resolved);
+            }
+            resolved.retain(|cmd| group_filter.as_ref().is_none_or(|filter| cmd.group == *filter));
+            return render(&resolved, format, COMMAND_COLUMNS);
+        }
         
         if resolved.is_empty() {
             println!("No commands saved.");
             return Ok(());
         }
 
+        if sort == SortOrder::Frecency {
+            UsageStore::load().sort_by_frecency(&mut resolved);
+        }
+
         for cmd in resolved {
             if let Some(ref filter) = group_filter {
                 if cmd.group != *filter {
@@ -64,22 +102,26 @@ impl SebasApp {
             if plain {
                 println!("{}", cmd.command.command);
             } else if verbose {
-                println!("[{}] {} ({}/{}) - ID: {}", 
+                println!("[{}] {} ({}/{}) - ID: {}{}{}", 
                     cmd.index,
                     cmd.command.command,
                     cmd.folder_path.parent().unwrap_or(&cmd.folder_path).display(),
                     cmd.group,
-                    cmd.command.hash
+                    cmd.command.hash,
+                    Self::variants_marker(&cmd),
+                    Self::untrusted_marker(&cmd)
                 );
                 if let Some(ref comment) = cmd.command.comment {
                     println!("    Comment: {}", comment);
                 }
             } else {
-                println!("[{}] {} ({}) - ID: {}", 
+                println!("[{}] {} ({}) - ID: {}{}{}", 
                     cmd.index,
                     cmd.command.command,
                     cmd.group,
-                    cmd.command.hash
+                    cmd.command.hash,
+                    Self::variants_marker(&cmd),
+                    Self::untrusted_marker(&cmd)
                 );
             }
         }
@@ -87,7 +129,21 @@ impl SebasApp {
         Ok(())
     }
 
-    pub fn edit_command(&self, identifier: &str, new_command: Option<String>, new_group: Option<String>, new_comment: Option<String>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    /// Warn about new command text that matches a danger rule, `false` when the user
+    /// does not want to save it.
+    pub fn confirm_dangerous_save(command: &SavedCommand, yes: bool) -> bool {
+        let matches = DangerChecker::for_dirs(&get_all_sebas_dirs()).check(command);
+        if matches.is_empty() {
+            return true;
+        }
+        println!("{} '{}' looks dangerous:", "Warning:".bright_yellow(), command.command);
+        for m in &matches {
+            println!("  {} {} ({})", "!".bright_red(), m.message, m.id.dimmed());
+        }
+        yes || Self::confirm("Save it anyway?")
+    }
+
+    pub fn edit_command(&self, identifier: &str, changes: CommandEdit, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
         let resolved_cmd = self.find_command_by_identifier(identifier)
             .ok_or("Command not found")?;
         
@@ -100,29 +156,54 @@ impl SebasApp {
         }
 
         // If changing group, we need to move the command
-        let target_group = new_group.as_ref().unwrap_or(&resolved_cmd.group);
+        let target_group = changes.group.as_ref().unwrap_or(&resolved_cmd.group);
         
         // Load current group and remove the command
         let app = SebasApp { sebas_dir: resolved_cmd.folder_path.clone() };
+        let target_app = SebasApp { sebas_dir: changes.folder.clone().unwrap_or_else(|| app.sebas_dir.clone()) };
+        // `--scope` paths and the chain can name the same folder differently
+        let canonical = |dir: &std::path::PathBuf| dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        let same_place = *target_group == resolved_cmd.group && canonical(&target_app.sebas_dir) == canonical(&app.sebas_dir);
         let mut current_group = app.load_group(&resolved_cmd.group)?;
+        let position = current_group.commands.iter().position(|cmd| cmd.hash == resolved_cmd.command.hash);
         current_group.commands.retain(|cmd| cmd.hash != resolved_cmd.command.hash);
         
         // Update command
         let mut updated_command = resolved_cmd.command.clone();
-        if let Some(cmd) = new_command {
-            updated_command.command = cmd;
+        if let Some(cmd) = changes.command {
+            updated_command.command = Self::redact_secrets(cmd, yes)?;
             updated_command.hash = Self::generate_hash(&updated_command.command);
         }
-        if let Some(comment) = new_comment {
+        if let Some(comment) = changes.comment {
             updated_command.comment = Some(comment);
         }
+        if let Some(name) = changes.name {
+            updated_command.name = Some(name);
+        }
+        if let Some(danger) = changes.danger {
+            updated_command.danger = danger;
+        }
+        if updated_command.command != resolved_cmd.command.command && !Self::confirm_dangerous_save(&updated_command, yes) {
+            println!("Edit cancelled.");
+            return Ok(());
+        }
 
-        // Save to target group
-        let mut target_group_data = app.load_group(target_group)?;
-        target_group_data.commands.push(updated_command);
-        
-        app.save_group(&resolved_cmd.group, &current_group)?;
-        app.save_group(target_group, &target_group_data)?;
+        // Save to target group, keeping the position when group and folder are unchanged
+        let mut files = app.group_files(&resolved_cmd.group);
+        files.extend(target_app.group_files(target_group));
+        Self::journaled(format!("edit '{}'", resolved_cmd.command.command), files, || {
+            if same_place {
+                let position = position.unwrap_or(current_group.commands.len()).min(current_group.commands.len());
+                current_group.commands.insert(position, updated_command);
+                app.save_group(&resolved_cmd.group, app.sebas_dir.join(format!("{}.yaml", resolved_cmd.group)), &current_group)
+            } else {
+                let mut target_group_data = target_app.load_group(target_group)?;
+                target_group_data.commands.push(updated_command);
+
+                app.save_group(&resolved_cmd.group, app.sebas_dir.join(format!("{}.yaml", resolved_cmd.group)), &current_group)?;
+                target_app.save_group(target_group, target_app.sebas_dir.join(format!("{}.yaml", target_group)), &target_group_data)
+            }
+        })?;
         
         println!("Command updated successfully.");
         Ok(())
@@ -132,58 +213,214 @@ impl SebasApp {
         let resolved_cmd = self.find_command_by_identifier(identifier)
             .ok_or("Command not found")?;
         
-        if !yes {
-            if !Self::confirm(&format!("Delete command: '{}'?", resolved_cmd.command.command)) {
-                println!("Deletion cancelled.");
-                return Ok(());
-            }
+        if !yes && !Self::confirm(&format!("Delete command: '{}'?", resolved_cmd.command.command)) {
+            println!("Deletion cancelled.");
+            return Ok(());
         }
 
         let app = SebasApp { sebas_dir: resolved_cmd.folder_path.clone() };
         let mut group = app.load_group(&resolved_cmd.group)?;
         group.commands.retain(|cmd| cmd.hash != resolved_cmd.command.hash);
-        app.save_group(&resolved_cmd.group, &group)?;
+
+        // The trash is plain YAML, so commands of encrypted groups are not kept there
+        let encrypted = app.sebas_dir.join(format!("{}.enc", resolved_cmd.group)).exists();
+        Self::expire_trash(&app.sebas_dir);
+        let entry = (!encrypted).then(|| TrashEntry::new(&resolved_cmd.group, TrashItem::Command { command: Box::new(resolved_cmd.command.clone()) }));
+        let mut files = app.group_files(&resolved_cmd.group);
+        files.extend(entry.as_ref().map(|entry| entry.path(&app.sebas_dir)));
+        Self::journaled(format!("remove '{}'", resolved_cmd.command.command), files, || {
+            if let Some(ref entry) = entry {
+                entry.write(&app.sebas_dir)?;
+            }
+            app.save_group(&resolved_cmd.group, app.sebas_dir.join(format!("{}.yaml", resolved_cmd.group)), &group)
+        })?;
         
-        println!("Command deleted successfully.");
+        match entry {
+            Some(entry) => println!("Command moved to the trash (restore with 'sebas trash restore {}').", entry.id),
+            None => println!("Command deleted, encrypted groups are not kept in the trash (only 'sebas undo' can bring it back)."),
+        }
         Ok(())
     }
 
     // Search in command history using query and fzf
-    pub fn history_commands(&self, query: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn history_commands(&self, _query: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
         Ok(())
     }
 
-    pub fn obtain_command(&self, identifier: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
-       let command = match identifier {
-            Some(id) => self.find_command_by_identifier(&id)
-            .ok_or("Command not found")?
+    pub fn select_command(&self, identifier: Option<String>, relevant: bool, profile: Option<&str>) -> Result<ResolvedCommand, Box<dyn std::error::Error>> {
+        Ok(self.select_command_with_keys(identifier, relevant, profile, &[])?.0)
+    }
+
+    /// `select_command`, also returning which of `expect` accepted the selection in fzf.
+    pub fn select_command_with_keys(&self, identifier: Option<String>, relevant: bool, profile: Option<&str>, expect: &[&str]) -> Result<(ResolvedCommand, Option<String>), Box<dyn std::error::Error>> {
+       let (mut command, key) = match identifier {
+            Some(id) => (self.find_command_by_identifier(&id)
+            .ok_or("Command not found")?, None)
 ,
-            None => sebas_fzf_run(self.resolve_all_commands()).context("Faild to run fzf")?,
+            None => {
+                let context = DirContext::detect();
+                let mut resolved = self.resolve_all_commands();
+                if relevant {
+                    resolved.retain(|cmd| context.relevance(&cmd.command) != Relevance::Irrelevant);
+                }
+                UsageStore::load().sort_by_frecency(&mut resolved);
+                context.sort_by_relevance(&mut resolved);
+                sebas_fzf_run(resolved, profile, expect).context("Faild to run fzf")?
+            }
         };
-        let _ = preseed(command);
+        command.select_variant();
+        Self::apply_profile(&mut command, profile)?;
+        Ok((command, key))
+    }
+
+    pub fn passes_danger_check(&self, command: &ResolvedCommand) -> bool {
+        let matches = DangerChecker::for_dirs(&get_all_sebas_dirs()).check(&command.command);
+        matches.is_empty() || Self::confirm_danger(command, &matches)
+    }
+
+    pub fn obtain_command(&self, identifier: Option<String>, relevant: bool, copy: bool, profile: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let (command, key) = self.select_command_with_keys(identifier, relevant, profile, &[COPY_KEY])?;
+        Self::ensure_trusted(&command)?;
+        if !self.passes_danger_check(&command) {
+            println!("Aborted.");
+            return Ok(());
+        }
+        if let Err(e) = self.record_usage(&command) {
+            eprintln!("Warning: failed to record usage: {}", e);
+        }
+
+        let action = if copy || key.is_some() { ObtainAction::Copy } else { FolderConfig::load(&self.sebas_dir).obtain.action };
+        match action {
+            ObtainAction::Preseed => {
+                let _ = preseed(command);
+            }
+            ObtainAction::Copy => {
+                let method = clipboard::copy(&command.command.command)?;
+                println!("Copied '{}' to the clipboard ({}).", command.command.command, method);
+            }
+        }
         Ok(())
     }
 
-    pub fn list_groups(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let groups = self.get_all_groups()?;
+    pub fn run_command(&self, identifier: Option<String>, relevant: bool, capture: bool, targets: &TargetArgs, profile: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let command = self.select_command(identifier, relevant, profile)?;
+        Self::ensure_trusted(&command)?;
+        if !self.passes_danger_check(&command) {
+            println!("Aborted.");
+            return Ok(());
+        }
+        if capture && command.folder_path.join(format!("{}.enc", command.group)).exists() {
+            return Err("Runs of encrypted groups are not captured, the log would keep their output in plain text.".into());
+        }
+        // Resolved up front so a pattern without matches does not count as a use
+        let dirs = targets.is_set().then(|| targets.resolve()).transpose()?;
+        if let Err(e) = self.record_usage(&command) {
+            eprintln!("Warning: failed to record usage: {}", e);
+        }
+
+        if let Some(dirs) = dirs {
+            return Self::run_in_targets(&command, &dirs, targets.jobs);
+        }
+
+        let mut process = command.process();
+        if let Some(dir) = command.working_dir() {
+            if !dir.is_dir() {
+                return Err(format!("Working directory {} does not exist.", dir.display()).into());
+            }
+            process.current_dir(dir);
+        }
+        let status = if capture { Self::run_captured(&command, process)? } else { process.status()? };
+        if !status.success() {
+            std::process::exit(status.code().unwrap_or(1));
+        }
+        Ok(())
+    }
+
+    pub fn list_groups(&self, format: Option<OutputFormat>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut summaries = Vec::new();
+        for group in self.get_all_groups()? {
+            let encrypted_file = self.sebas_dir.join(format!("{}.enc", group));
+            let encrypted = encrypted_file.exists();
+            let locked = encrypted && Self::read_encrypted_group(&encrypted_file)?.is_none();
+            let commands = if locked { None } else { Some(self.load_group(&group)?.commands.len()) };
+            summaries.push(GroupSummary {
+                name: group,
+                folder_path: self.sebas_dir.clone(),
+                commands,
+                encrypted,
+                locked,
+            });
+        }
+
+        if let Some(format) = format {
+            return render(&summaries, format, GROUP_COLUMNS);
+        }
         
-        if groups.is_empty() {
+        if summaries.is_empty() {
             println!("No groups found.");
             return Ok(());
         }
 
-        for group in groups {
-            let group_data = self.load_group(&group)?;
-            let folder_name = self.sebas_dir
-                .parent()
-                .and_then(|p| p.file_name())
-                .and_then(|n| n.to_str())
-                .unwrap_or("unknown");
-            
-            println!("{}/{} ({} commands)", folder_name, group, group_data.commands.len());
+        let folder_name = self.sebas_dir
+            .parent()
+            .and_then(|p| p.file_name())
+            .and_then(|n| n.to_str())
+            .unwrap_or("unknown");
+        for summary in summaries {
+            match summary.commands {
+                Some(count) => println!("{}/{} ({} commands)", folder_name, summary.name, count),
+                None => println!("{}/{} (encrypted, locked)", folder_name, summary.name),
+            }
         }
         
         Ok(())
     }
+
+    pub fn show_command(&self, identifier: &str, format: Option<OutputFormat>) -> Result<(), Box<dyn std::error::Error>> {
+        let cmd = self.find_command_by_identifier(identifier).ok_or("Command not found")?;
+        if let Some(format) = format {
+            // A single object rather than a one element array
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&cmd)?),
+                OutputFormat::Jsonl => println!("{}", serde_json::to_string(&cmd)?),
+                OutputFormat::Yaml => print!("{}", serde_yaml::to_string(&cmd)?),
+                _ => render(&[cmd], format, COMMAND_COLUMNS)?,
+            }
+            return Ok(());
+        }
+
+        let saved = &cmd.command;
+        println!("{} {}", "Command:".bold(), saved.command);
+        if let Some(ref name) = saved.name {
+            println!("{} {}", "Name:".bold(), name);
+        }
+        if let Some(ref comment) = saved.comment {
+            println!("{} {}", "Comment:".bold(), comment);
+        }
+        println!("{} {}", "Group:".bold(), cmd.group);
+        println!("{} {}", "Folder:".bold(), cmd.folder_path.display());
+        println!("{} {} (index {})", "Hash:".bold(), saved.hash, cmd.index);
+        println!("{} {}", "Created:".bold(), saved.created_at);
+        println!("{} {}", "Trusted:".bold(), if cmd.trusted { "yes" } else { "no" });
+        if saved.danger {
+            println!("{} yes", "Dangerous:".bold());
+        }
+        if let Some(ref when) = saved.when {
+            print!("{}\n{}", "When:".bold(), serde_yaml::to_string(when)?);
+        }
+        if !saved.vars.is_empty() {
+            print!("{}\n{}", "Vars:".bold(), serde_yaml::to_string(&saved.vars)?);
+        }
+        if let Some(dir) = cmd.working_dir() {
+            println!("{} {}", "Cwd:".bold(), dir.display());
+        }
+        if !saved.env.is_empty() {
+            print!("{}\n{}", "Env:".bold(), serde_yaml::to_string(&saved.env)?);
+        }
+        if !saved.variants.is_empty() {
+            print!("{}\n{}", "Variants:".bold(), serde_yaml::to_string(&saved.variants)?);
+        }
+        Ok(())
+    }
 }
 
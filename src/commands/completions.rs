@@ -0,0 +1,73 @@
+use std::{collections::BTreeSet, ffi::OsStr, io};
+
+use clap::CommandFactory;
+use clap_complete::{engine::{CompletionCandidate, PathCompleter, ValueCompleter}, env::Shells, Shell};
+
+use crate::{utils::dir::get_all_sebas_dirs, Cli, SebasApp};
+
+// Completers run on every <Tab>, so they stay quiet and skip locked groups silently
+
+fn apps() -> Vec<SebasApp> {
+    get_all_sebas_dirs().into_iter().map(|sebas_dir| SebasApp { sebas_dir }).collect()
+}
+
+/// Group names of every folder in the chain.
+pub fn complete_groups() -> Vec<CompletionCandidate> {
+    let groups: BTreeSet<String> = apps()
+        .iter()
+        .flat_map(|app| app.get_all_groups().unwrap_or_default())
+        .collect();
+    groups.into_iter().map(CompletionCandidate::new).collect()
+}
+
+/// Names and hashes of saved commands, described by their comment or text.
+pub fn complete_identifiers() -> Vec<CompletionCandidate> {
+    let mut candidates = Vec::new();
+    for app in apps() {
+        for group in app.get_all_groups().unwrap_or_default() {
+            let Ok(data) = app.load_group(&group) else {
+                continue;
+            };
+            for cmd in data.commands {
+                let help = cmd.comment.clone().unwrap_or_else(|| cmd.command.clone());
+                if let Some(name) = cmd.name {
+                    candidates.push(CompletionCandidate::new(name).help(Some(help.clone().into())));
+                }
+                candidates.push(CompletionCandidate::new(cmd.hash).help(Some(format!("{} ({})", help, group).into())));
+            }
+        }
+    }
+    candidates
+}
+
+/// Known `.sebas` folders first, then any directory.
+pub fn complete_sebas_paths(current: &OsStr) -> Vec<CompletionCandidate> {
+    let prefix = current.to_string_lossy();
+    let mut candidates: Vec<CompletionCandidate> = get_all_sebas_dirs()
+        .into_iter()
+        .map(|dir| dir.display().to_string())
+        .filter(|dir| dir.starts_with(prefix.as_ref()))
+        .map(|dir| CompletionCandidate::new(dir).help(Some("sebas folder".into())))
+        .collect();
+    candidates.extend(PathCompleter::dir().complete(current));
+    candidates
+}
+
+impl SebasApp {
+    /// Print the completion script for `shell`. The default script calls back into
+    /// sebas for group names and identifiers, `static_only` leaves those out.
+    pub fn print_completions(shell: Shell, static_only: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut cmd = Cli::command();
+        if static_only {
+            clap_complete::generate(shell, &mut cmd, "sebas", &mut io::stdout());
+            return Ok(());
+        }
+
+        let shells = Shells::builtins();
+        let completer = shells
+            .completer(&shell.to_string())
+            .ok_or_else(|| format!("Dynamic completions are not available for {}, use --static.", shell))?;
+        completer.write_registration("COMPLETE", "sebas", "sebas", "sebas", &mut io::stdout())?;
+        Ok(())
+    }
+}
@@ -1,20 +1,66 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 pub struct ResolvedCommand {
+    #[serde(flatten)]
     pub(crate) command: SavedCommand,
     pub(crate) group: String,
     pub(crate) folder_path: PathBuf,
    pub(crate) index: usize,
+    /// Whether the folder is on the user's trust allowlist
+    pub(crate) trusted: bool,
+    /// Key of the variant swapped in for this shell or OS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) variant: Option<String>,
+    /// Profile whose variables were filled in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) profile: Option<String>,
 }
 #[derive(Serialize, Deserialize,
     Debug, Clone)]
 pub struct SavedCommand {
     pub(crate) command: String,
+    /// Short name, used as identifier and for exported aliases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
     pub(crate)comment: Option<String>,
     pub(crate) hash: String,
     pub(crate) created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) when: Option<When>,
+    /// Shell commands listing candidate values for `<name>` placeholders
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) vars: BTreeMap<String, String>,
+    /// Directory to run in, relative to the folder holding `.sebas` unless absolute
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cwd: Option<String>,
+    /// Environment variables set for the command
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) env: BTreeMap<String, String>,
+    /// Replacements for `command` keyed by shell (`fish`, `nu`, ...) or OS (`macos`,
+    /// `windows`, ...); the current shell is tried before the OS
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) variants: BTreeMap<String, String>,
+    /// Always require confirmation, even if no danger rule matches
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) danger: bool,
+}
+/// Conditions under which a command is relevant; all listed must hold.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct When {
+    /// Files that must exist in the current directory or one of its parents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) files: Vec<String>,
+    /// Git branch pattern, `*` matches any run of characters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) branch: Option<String>,
+    /// Environment variables that must be set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) env: Vec<String>,
+    /// Detected contexts such as `rust` or `docker`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) contexts: Vec<String>,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CommandGroup {
@@ -22,3 +68,92 @@ pub struct CommandGroup {
 }
 
 
+
+/// Settings stored in `.sebas/.config.yaml`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FolderConfig {
+    #[serde(default)]
+    pub(crate) danger: DangerConfig,
+    #[serde(default)]
+    pub(crate) trash: TrashConfig,
+    #[serde(default)]
+    pub(crate) capture: CaptureConfig,
+    #[serde(default)]
+    pub(crate) obtain: ObtainConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ObtainConfig {
+    /// What `sebas obtain` does with the chosen command unless `--copy` is given
+    #[serde(default)]
+    pub(crate) action: ObtainAction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ObtainAction {
+    /// Type the command into the prompt
+    #[default]
+    Preseed,
+    /// Put the command on the clipboard
+    Copy,
+}
+
+/// When the shell hook suggests bookmarking a command.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// Commands at least this long are suggested after one run, 0 turns this off
+    pub(crate) min_length: usize,
+    /// Commands run this often within `days` are suggested, 0 turns this off
+    pub(crate) repeat: usize,
+    pub(crate) days: i64,
+    /// Ignore runs that exited with an error
+    pub(crate) success_only: bool,
+}
+
+impl Default for CaptureConfig {
+    fn default() -> Self {
+        Self { min_length: 60, repeat: 3, days: 7, success_only: true }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashConfig {
+    /// Days removed commands and groups are kept, 0 keeps them forever
+    #[serde(default = "TrashConfig::default_expire_days")]
+    pub(crate) expire_days: i64,
+}
+
+impl TrashConfig {
+    fn default_expire_days() -> i64 {
+        30
+    }
+}
+
+impl Default for TrashConfig {
+    fn default() -> Self {
+        Self { expire_days: Self::default_expire_days() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DangerConfig {
+    /// Extra rules on top of the built-in ones
+    #[serde(default)]
+    pub(crate) rules: Vec<DangerRule>,
+    /// Ids of built-in rules to turn off
+    #[serde(default)]
+    pub(crate) disable: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DangerRule {
+    pub(crate) id: String,
+    /// Regex matched against the command text
+    pub(crate) pattern: String,
+    pub(crate) message: String,
+    /// Only flag when the kubectl context matches this regex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) kube_context: Option<String>,
+}
@@ -1,7 +1,7 @@
-use std::{fs, io::{self, Read, Write}};
-use std::process::Command as ProcessCommand;
+use std::{env, fs, io::{self, BufRead, Read, Write}, path::{Path, PathBuf}, process::Command as ProcessCommand};
 use sha2::{Digest, Sha256};
-use crate::{commands::core::definition::{CommandGroup, ResolvedCommand}, utils::dir::{find_sebas_dir, get_all_sebas_dirs}, SebasApp};
+use colored::Colorize;
+use crate::{commands::{capture::definition::CaptureStore, core::definition::{CommandGroup, FolderConfig, ResolvedCommand}, trust::definition::TrustStore}, utils::{danger::DangerMatch, secrets::{self, Redaction}, shell::{current_shell, is_posix_shell, is_valid_env_name, quote, SHELLS}}, utils::dir::{find_sebas_dir, get_all_sebas_dirs, is_group_file}, SebasApp};
 impl CommandGroup {
     pub fn new() -> Self {
         Self {
@@ -10,6 +10,67 @@ impl CommandGroup {
     }
 }
 
+impl ResolvedCommand {
+    /// Where the command runs, `None` for the current directory.
+    pub fn working_dir(&self) -> Option<PathBuf> {
+        let cwd = Path::new(self.command.cwd.as_deref()?);
+        let project = self.folder_path.parent().unwrap_or(&self.folder_path);
+        Some(if cwd.is_absolute() { cwd.to_path_buf() } else { project.join(cwd) })
+    }
+
+    /// Swap in the variant for the current shell, or else for the current OS.
+    pub fn select_variant(&mut self) {
+        let variants = &self.command.variants;
+        let key = current_shell()
+            .filter(|shell| variants.contains_key(shell))
+            .or_else(|| Some(env::consts::OS.to_string()).filter(|os| variants.contains_key(os)));
+        if let Some(key) = key {
+            self.command.command = variants[&key].clone();
+            self.variant = Some(key);
+        }
+    }
+
+    /// Shell that runs the command: the one its variant was written for, bash otherwise.
+    pub fn runner(&self) -> &str {
+        self.variant.as_deref().filter(|variant| SHELLS.contains(variant)).unwrap_or("bash")
+    }
+
+    /// A process running the command with its environment, in the current directory.
+    pub fn process(&self) -> ProcessCommand {
+        let mut process = ProcessCommand::new(self.runner());
+        process.arg("-c").arg(&self.command.command).envs(&self.command.env);
+        process
+    }
+
+    /// `command` as typed at a prompt, prefixed with the `cd` and variables it needs.
+    /// The prefix is POSIX syntax, so variants for other shells can not have one.
+    pub fn shell_line(&self, command: &str) -> Result<String, String> {
+        let needs_prefix = self.command.cwd.is_some() || !self.command.env.is_empty();
+        if needs_prefix && !is_posix_shell(self.runner()) {
+            return Err(format!("The directory and variables can only be prefixed for POSIX shells, not {}. Use 'sebas run' instead.", self.runner()));
+        }
+        let mut line = String::new();
+        if let Some(dir) = self.working_dir() {
+            line.push_str(&format!("cd {} && ", quote(&dir.to_string_lossy())));
+        }
+        // A hand-edited name that is not a valid variable would end up as a command
+        for (name, value) in self.command.env.iter().filter(|(name, _)| is_valid_env_name(name)) {
+            line.push_str(&format!("{}={} ", name, quote(value)));
+        }
+        line.push_str(command);
+        Ok(line)
+    }
+}
+
+impl FolderConfig {
+    pub fn load(sebas_dir: &Path) -> Self {
+        fs::read_to_string(sebas_dir.join(".config.yaml"))
+            .ok()
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
 impl SebasApp {
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let sebas_dir = find_sebas_dir()
@@ -39,19 +100,9 @@ impl SebasApp {
         None
     }
 
+    /// The last command seen by `sebas shell-hook`.
     pub fn get_last_shell_command() -> Option<String> {
-        let output = ProcessCommand::new("bash")
-            .arg("-c")
-            .arg("fc -ln -1")
-            .output()
-            .ok()?;
-        
-        let command = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if command.is_empty() || command.starts_with("sebas") {
-            None
-        } else {
-            Some(command)
-        }
+        CaptureStore::load().last
     }
 
     pub fn confirm(prompt: &str) -> bool {
@@ -62,31 +113,129 @@ impl SebasApp {
         matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
     }
 
+    /// Run a write to `sebas_dir`, re-stamping its trust if it was trusted before
+    /// and committing the change when the folder is versioned.
+    pub fn write_sebas_dir<T>(sebas_dir: &Path, write: impl FnOnce() -> Result<T, Box<dyn std::error::Error>>) -> Result<T, Box<dyn std::error::Error>> {
+        let mut store = TrustStore::load();
+        let was_trusted = store.is_trusted(sebas_dir);
+        let result = write()?;
+        if let Err(e) = Self::commit_sebas_dir(sebas_dir) {
+            eprintln!("Warning: failed to record the change in history: {}", e);
+        }
+        if was_trusted {
+            store.trust(sebas_dir);
+            store.save()?;
+        }
+        Ok(result)
+    }
+
+    // Dangerous commands need the hash typed back, a plain y is too easy
+    pub fn confirm_danger(resolved_command: &ResolvedCommand, matches: &[DangerMatch]) -> bool {
+        Self::confirm_danger_on(resolved_command, matches, &mut io::stdout(), &mut io::stdin().lock()).unwrap_or(false)
+    }
+
+    /// `confirm_danger` on another terminal, such as /dev/tty when stdout is captured.
+    pub fn confirm_danger_on(resolved_command: &ResolvedCommand, matches: &[DangerMatch], out: &mut impl Write, input: &mut impl BufRead) -> io::Result<bool> {
+        writeln!(out, "{} {}", "Dangerous command:".bright_red().bold(), resolved_command.command.command)?;
+        for m in matches {
+            writeln!(out, "  {} {} ({})", "!".bright_red(), m.message, m.id.dimmed())?;
+        }
+        write!(out, "Type '{}' to continue: ", resolved_command.command.hash)?;
+        out.flush()?;
+        let mut line = String::new();
+        input.read_line(&mut line)?;
+        Ok(line.trim() == resolved_command.command.hash)
+    }
+
+    // Offer to swap likely secrets for references before anything is written
+    pub fn redact_secrets(command: String, yes: bool) -> Result<String, Box<dyn std::error::Error>> {
+        let findings = secrets::scan(&command);
+        let mut replacements = Vec::new();
+
+        for finding in &findings {
+            let secret = &command[finding.start..finding.end];
+            let env_form = secrets::replacement(&command, finding, Redaction::Env);
+            let placeholder = secrets::replacement(&command, finding, Redaction::Placeholder);
+
+            println!("{} {} {}", "Possible secret:".bright_yellow(), finding.kind, secrets::mask(secret).dimmed());
+            let choice = if yes {
+                "e".to_string()
+            } else {
+                print!("  Replace with [e]nv var {}, [p]laceholder {}, [k]eep or [a]bort? (E/p/k/a) ", env_form, placeholder);
+                io::stdout().flush()?;
+                let mut input = String::new();
+                io::stdin().read_line(&mut input)?;
+                input.trim().to_lowercase()
+            };
+
+            if yes {
+                println!("  {} {}", "Replaced with".dimmed(), env_form);
+            }
+            match choice.as_str() {
+                "" | "e" | "env" => replacements.push((finding, env_form)),
+                "p" | "placeholder" => replacements.push((finding, placeholder)),
+                "k" | "keep" => {}
+                _ => return Err("Aborted, command not saved.".into()),
+            }
+        }
+
+        // Replace from the end so earlier offsets stay valid
+        let mut redacted = command.clone();
+        for (finding, replacement) in replacements.iter().rev() {
+            redacted.replace_range(finding.start..finding.end, replacement);
+        }
+
+        if redacted != command {
+            println!("{} {}", "Saving as:".bright_blue(), redacted);
+        }
+        Ok(redacted)
+    }
+
     pub fn resolve_all_commands(&self) -> Vec<ResolvedCommand> {
         let mut resolved = Vec::new();
         let dirs = get_all_sebas_dirs();
+        let trust = TrustStore::load();
         
         for dir in dirs {
+            let trusted = trust.is_trusted(&dir);
             if let Ok(entries) = fs::read_dir(&dir) {
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
-                            if let Some(group_name) = path.file_stem().and_then(|s| s.to_str()) {
-                                if let Ok(content) = fs::read_to_string(&path) {
-                                    if let Ok(group) = serde_yaml::from_str::<CommandGroup>(&content) {
-                                        for (cmd_idx, cmd) in group.commands.iter().enumerate() {
-                                            resolved.push(ResolvedCommand {
-                                                command: cmd.clone(),
-                                                group: group_name.to_string(),
-                                                folder_path: dir.clone(),
-                                                index: resolved.len() + 1,
-                                            });
-                                        }
-                                    }
-                                }
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    let Some(group_name) = path.file_stem().and_then(|s| s.to_str()) else {
+                        continue;
+                    };
+                    let group = if is_group_file(&path) {
+                        fs::read_to_string(&path)
+                            .ok()
+                            .and_then(|content| serde_yaml::from_str::<CommandGroup>(&content).ok())
+                    } else if path.extension().and_then(|s| s.to_str()) == Some("enc") {
+                        match Self::read_encrypted_group(&path) {
+                            Ok(Some(group)) => Some(group),
+                            Ok(None) => {
+                                eprintln!("Skipping locked group '{}' in {} (run 'sebas unlock')", group_name, dir.display());
+                                None
+                            }
+                            Err(e) => {
+                                eprintln!("Skipping encrypted group '{}' in {}: {}", group_name, dir.display(), e);
+                                None
                             }
                         }
+                    } else {
+                        None
+                    };
+
+                    if let Some(group) = group {
+                        for cmd in group.commands.iter() {
+                            resolved.push(ResolvedCommand {
+                                command: cmd.clone(),
+                                group: group_name.to_string(),
+                                folder_path: dir.clone(),
+                                index: resolved.len() + 1,
+                                trusted,
+                                variant: None,
+                                profile: None,
+                            });
+                        }
                     }
                 }
             }
@@ -103,7 +252,10 @@ impl SebasApp {
             return  resolved.get(index - 1).cloned();
         }
         
-        // Try to find by hash
+        // Try to find by name, then by hash
+        if let Some(cmd) = resolved.iter().find(|cmd| cmd.command.name.as_deref() == Some(identifier)) {
+            return Some(cmd.clone());
+        }
         resolved.into_iter().find(|cmd| cmd.command.hash.starts_with(identifier))
     }
 }
@@ -0,0 +1,31 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// One command as rendered in the `sebas edit -i` buffer.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EditEntry {
+    /// Hash of the command before editing, identifies the entry
+    pub(crate) id: String,
+    pub(crate) group: String,
+    pub(crate) command: String,
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) comment: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) danger: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cwd: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) env: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) variants: BTreeMap<String, String>,
+}
+
+/// The temporary YAML file edited by `sebas edit -i`, in a private folder that is
+/// removed with it.
+pub struct EditBuffer {
+    pub(crate) dir: PathBuf,
+    pub(crate) path: PathBuf,
+}
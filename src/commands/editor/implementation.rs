@@ -0,0 +1,248 @@
+use std::{collections::{BTreeMap, HashSet}, env, fs, io::Write, path::Path, process::Command as ProcessCommand};
+
+use colored::Colorize;
+
+use crate::{commands::{core::definition::{CommandGroup, SavedCommand}, editor::definition::{EditBuffer, EditEntry}}, utils::{dir::{create_private_dir, create_private_file, is_valid_group_name}, shell::is_valid_env_name}, SebasApp};
+
+const BUFFER_HEADER: &str = "\
+# Edit the commands below, save and quit to apply.
+# Change `group` to move a command, multi-line commands can use `command: |`.
+# Entries removed from this buffer are left untouched. Keep the `id` lines as they are.
+";
+
+impl EditBuffer {
+    // A fresh folder per edit, so the name can not be guessed or planted in advance
+    fn create(content: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = env::temp_dir().join(format!("sebas-edit-{}", uuid::Uuid::new_v4().simple()));
+        create_private_dir(&dir)?;
+        let buffer = Self { path: dir.join("commands.yaml"), dir };
+        create_private_file(&buffer.path)?.write_all(content.as_bytes())?;
+        Ok(buffer)
+    }
+}
+
+impl Drop for EditBuffer {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+impl SebasApp {
+    fn open_editor(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let editor = env::var("VISUAL")
+            .or_else(|_| env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        // Through the shell so editors with arguments like "code --wait" work
+        let status = ProcessCommand::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$1\"", editor))
+            .arg("sh")
+            .arg(path)
+            .status()?;
+        if !status.success() {
+            return Err(format!("Editor '{}' exited with {}", editor, status).into());
+        }
+        Ok(())
+    }
+
+    fn validate_buffer(content: &str, originals: &BTreeMap<String, (String, SavedCommand)>) -> Result<Vec<EditEntry>, String> {
+        let entries: Vec<EditEntry> = serde_yaml::from_str::<Option<Vec<EditEntry>>>(content)
+            .map_err(|e| format!("Invalid YAML: {}", e))?
+            .unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        for entry in &entries {
+            if !originals.contains_key(&entry.id) {
+                return Err(format!("Unknown id '{}', new commands can not be added here.", entry.id));
+            }
+            if !seen.insert(&entry.id) {
+                return Err(format!("Id '{}' appears more than once.", entry.id));
+            }
+            if entry.command.trim().is_empty() {
+                return Err(format!("Command of '{}' is empty.", entry.id));
+            }
+            if let Some((key, _)) = entry.variants.iter().find(|(_, command)| command.trim().is_empty()) {
+                return Err(format!("Variant '{}' of '{}' is empty.", key, entry.id));
+            }
+            if let Some(name) = entry.env.keys().find(|name| !is_valid_env_name(name)) {
+                return Err(format!("'{}' is not a valid environment variable name.", name));
+            }
+            if !is_valid_group_name(&entry.group) {
+                return Err(format!("'{}' is not a valid group name.", entry.group));
+            }
+        }
+        Ok(entries)
+    }
+
+    // Variables on one line in the diff, `None` when there are none
+    fn env_line(env: &BTreeMap<String, String>) -> Option<String> {
+        (!env.is_empty()).then(|| env.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(" "))
+    }
+
+    fn variants_text(variants: &BTreeMap<String, String>) -> Option<String> {
+        (!variants.is_empty()).then(|| variants.iter().map(|(key, command)| format!("{}: {}", key, command)).collect::<Vec<_>>().join("\n"))
+    }
+
+    fn print_entry_diff(before: &EditEntry, after: &EditEntry) {
+        println!("{}", format!("{} ({})", before.id, before.group).bold());
+        let fields = [
+            ("group", Some(before.group.clone()), Some(after.group.clone())),
+            ("command", Some(before.command.clone()), Some(after.command.clone())),
+            ("name", before.name.clone(), after.name.clone()),
+            ("comment", before.comment.clone(), after.comment.clone()),
+            ("danger", Some(before.danger.to_string()), Some(after.danger.to_string())),
+            ("cwd", before.cwd.clone(), after.cwd.clone()),
+            ("env", Self::env_line(&before.env), Self::env_line(&after.env)),
+            ("variants", Self::variants_text(&before.variants), Self::variants_text(&after.variants)),
+        ];
+        for (field, old, new) in fields {
+            if old == new {
+                continue;
+            }
+            if let Some(old) = old {
+                println!("{}", format!("- {}: {}", field, old.replace('\n', "\n-   ")).red());
+            }
+            if let Some(new) = new {
+                println!("{}", format!("+ {}: {}", field, new.replace('\n', "\n+   ")).green());
+            }
+        }
+    }
+
+    /// Edit one command, or a whole group of the nearest folder, as YAML in `$EDITOR`.
+    pub fn edit_interactive(&self, identifier: Option<String>, group: Option<String>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let (app, selected) = match (identifier, group) {
+            (Some(identifier), _) => {
+                let resolved = self.find_command_by_identifier(&identifier).ok_or("Command not found")?;
+                let app = SebasApp { sebas_dir: resolved.folder_path.clone() };
+                (app, vec![(resolved.group, resolved.command)])
+            }
+            (None, Some(group)) => {
+                if !self.get_all_groups()?.contains(&group) {
+                    return Err(format!("Group '{}' not found.", group).into());
+                }
+                let commands = self.load_group(&group)?.commands;
+                let app = SebasApp { sebas_dir: self.sebas_dir.clone() };
+                (app, commands.into_iter().map(|cmd| (group.clone(), cmd)).collect())
+            }
+            (None, None) => return Err("Pass a command identifier or --group to edit.".into()),
+        };
+        if selected.is_empty() {
+            println!("No commands to edit.");
+            return Ok(());
+        }
+
+        let originals: BTreeMap<String, (String, SavedCommand)> = selected
+            .iter()
+            .map(|(group, cmd)| (cmd.hash.clone(), (group.clone(), cmd.clone())))
+            .collect();
+        let rendered: Vec<EditEntry> = selected
+            .iter()
+            .map(|(group, cmd)| EditEntry {
+                id: cmd.hash.clone(),
+                group: group.clone(),
+                command: cmd.command.clone(),
+                name: cmd.name.clone(),
+                comment: cmd.comment.clone(),
+                danger: cmd.danger,
+                cwd: cmd.cwd.clone(),
+                env: cmd.env.clone(),
+                variants: cmd.variants.clone(),
+            })
+            .collect();
+
+        let buffer = EditBuffer::create(&format!("{}{}", BUFFER_HEADER, serde_yaml::to_string(&rendered)?))?;
+
+        // Reopen the editor until the buffer is valid or the user gives up
+        let edited = loop {
+            Self::open_editor(&buffer.path)?;
+            match Self::validate_buffer(&fs::read_to_string(&buffer.path)?, &originals) {
+                Ok(entries) => break entries,
+                Err(e) => {
+                    println!("{} {}", "Error:".bright_red(), e);
+                    if !Self::confirm("Edit again?") {
+                        println!("Edit cancelled.");
+                        return Ok(());
+                    }
+                }
+            }
+        };
+        drop(buffer);
+
+        let changed: Vec<(&EditEntry, EditEntry)> = edited
+            .into_iter()
+            .filter_map(|after| {
+                let before = rendered.iter().find(|entry| entry.id == after.id)?;
+                (*before != after).then_some((before, after))
+            })
+            .collect();
+        if changed.is_empty() {
+            println!("No changes.");
+            return Ok(());
+        }
+
+        for (before, after) in &changed {
+            Self::print_entry_diff(before, after);
+        }
+        if !yes && !Self::confirm(&format!("Apply changes to {} commands?", changed.len())) {
+            println!("Edit cancelled.");
+            return Ok(());
+        }
+
+        // Load every touched group first so nothing is written if one fails
+        let mut groups: BTreeMap<String, CommandGroup> = BTreeMap::new();
+        for (before, after) in &changed {
+            for name in [&before.group, &after.group] {
+                if !groups.contains_key(name) {
+                    groups.insert(name.clone(), app.load_group(name)?);
+                }
+            }
+        }
+
+        for (before, after) in changed {
+            let mut updated = originals[&before.id].1.clone();
+            if after.command != before.command {
+                updated.command = Self::redact_secrets(after.command, yes)?;
+                updated.hash = Self::generate_hash(&updated.command);
+            }
+            updated.name = after.name;
+            updated.comment = after.comment;
+            updated.danger = after.danger;
+            updated.cwd = after.cwd;
+            updated.env = after.env;
+            updated.variants = after.variants;
+            if updated.command != before.command && !Self::confirm_dangerous_save(&updated, yes) {
+                println!("Edit cancelled.");
+                return Ok(());
+            }
+
+            let source = groups.get_mut(&before.group).ok_or("Group vanished while editing")?;
+            let position = source.commands.iter().position(|cmd| cmd.hash == before.id);
+            if after.group == before.group {
+                match position {
+                    Some(position) => source.commands[position] = updated,
+                    None => source.commands.push(updated),
+                }
+            } else {
+                if let Some(position) = position {
+                    source.commands.remove(position);
+                }
+                groups.get_mut(&after.group).ok_or("Group vanished while editing")?.commands.push(updated);
+            }
+        }
+
+        let files = groups.keys().flat_map(|name| app.group_files(name)).collect();
+        let count = groups.len();
+        // One write for every group, so the folder history gets a single commit
+        Self::journaled("edit in editor".to_string(), files, || {
+            Self::write_sebas_dir(&app.sebas_dir, || {
+                for (name, group) in &groups {
+                    Self::write_group_file(&app.sebas_dir.join(format!("{}.yaml", name)), group)?;
+                }
+                Ok(())
+            })
+        })?;
+
+        println!("Updated {} groups.", count);
+        Ok(())
+    }
+}
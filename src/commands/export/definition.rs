@@ -0,0 +1,15 @@
+use clap::ValueEnum;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    /// Markdown cheatsheet grouped by group
+    Md,
+    /// JSON including the folder each command comes from
+    Json,
+    /// YAML including the folder each command comes from
+    Yaml,
+    /// Sourceable shell aliases for named commands
+    Aliases,
+    /// Sourceable shell functions for named commands
+    Functions,
+}
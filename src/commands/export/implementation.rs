@@ -0,0 +1,135 @@
+use std::{collections::{BTreeMap, HashSet}, fmt::Write as _, fs, path::PathBuf};
+
+use crate::{commands::{core::definition::ResolvedCommand, export::definition::ExportFormat}, utils::{placeholders::placeholders, shell::{is_valid_identifier, quote}}, SebasApp};
+
+impl SebasApp {
+    pub fn export_commands(&self, format: ExportFormat, group_filter: Option<String>, output: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut resolved = self.resolve_all_commands();
+        if let Some(ref filter) = group_filter {
+            resolved.retain(|cmd| cmd.group == *filter);
+        }
+        // Sourced shell code runs like the commands themselves, so it needs the same trust
+        if matches!(format, ExportFormat::Aliases | ExportFormat::Functions) {
+            let mut skipped: Vec<&PathBuf> = resolved.iter().filter(|cmd| !cmd.trusted).map(|cmd| &cmd.folder_path).collect();
+            skipped.dedup();
+            for folder in skipped {
+                eprintln!("Skipping untrusted {}, review it with 'sebas trust' first.", folder.display());
+            }
+            resolved.retain(|cmd| cmd.trusted);
+        }
+
+        let content = match format {
+            ExportFormat::Md => Self::export_markdown(&resolved),
+            ExportFormat::Json => serde_json::to_string_pretty(&resolved)? + "\n",
+            ExportFormat::Yaml => serde_yaml::to_string(&resolved)?,
+            ExportFormat::Aliases | ExportFormat::Functions => Self::export_shell(&resolved, format),
+        };
+
+        match output {
+            Some(path) => {
+                fs::write(&path, content)?;
+                eprintln!("Exported {} commands to {}", resolved.len(), path.display());
+            }
+            None => print!("{}", content),
+        }
+        Ok(())
+    }
+
+    fn export_markdown(resolved: &[ResolvedCommand]) -> String {
+        let mut groups: BTreeMap<&str, Vec<&ResolvedCommand>> = BTreeMap::new();
+        for cmd in resolved {
+            groups.entry(cmd.group.as_str()).or_default().push(cmd);
+        }
+
+        let mut out = String::from("# SEBAS Commands\n");
+        for (group, commands) in groups {
+            let _ = write!(out, "\n## {}\n", group);
+            for cmd in commands {
+                out.push('\n');
+                match (&cmd.command.comment, &cmd.command.name) {
+                    (Some(comment), Some(name)) => { let _ = writeln!(out, "**{}** (`{}`)\n", comment, name); }
+                    (Some(comment), None) => { let _ = writeln!(out, "**{}**\n", comment); }
+                    (None, Some(name)) => { let _ = writeln!(out, "`{}`\n", name); }
+                    (None, None) => {}
+                }
+                let _ = writeln!(out, "```sh\n{}\n```", cmd.command.command);
+            }
+        }
+        out
+    }
+
+    fn export_shell(resolved: &[ResolvedCommand], format: ExportFormat) -> String {
+        let mut out = String::from("# Generated by sebas export, source this file from your shell rc\n");
+        let mut seen = HashSet::new();
+
+        // Nearer folders are resolved first, so their names shadow the outer ones
+        for cmd in resolved {
+            let Some(name) = cmd.command.name.as_deref() else {
+                continue;
+            };
+            if !is_valid_identifier(name) {
+                eprintln!("Skipping '{}': not a valid shell name", name);
+                continue;
+            }
+            if !seen.insert(name) {
+                continue;
+            }
+            // `<name>` would be read as a redirection by the shell
+            if !placeholders(&cmd.command.command).is_empty() {
+                eprintln!("Skipping '{}': it has placeholders, use 'sebas pick {}'", name, name);
+                continue;
+            }
+            let Some(body) = Self::shell_body(cmd, format) else {
+                continue;
+            };
+
+            if let Some(ref comment) = cmd.command.comment {
+                let _ = writeln!(out, "\n# {}", comment.replace('\n', " "));
+            } else {
+                out.push('\n');
+            }
+            match format {
+                ExportFormat::Functions => {
+                    let _ = writeln!(out, "{}() {{\n    {}\n}}", name, body.replace('\n', "\n    "));
+                }
+                _ => {
+                    let _ = writeln!(out, "alias {}={}", name, quote(&body));
+                }
+            }
+        }
+        out
+    }
+
+    // The command with its directory and variables, arguments passed on where the
+    // shell allows it. `None` when it can not be exported in `format`.
+    fn shell_body(cmd: &ResolvedCommand, format: ExportFormat) -> Option<String> {
+        let name = cmd.command.name.as_deref().unwrap_or_default();
+        let text = &cmd.command.command;
+        let prefixed = cmd.command.cwd.is_some() || !cmd.command.env.is_empty();
+        if prefixed && text.contains('\n') {
+            eprintln!("Skipping '{}': a multi-line command can not take its directory and variables as a prefix", name);
+            return None;
+        }
+        let line = match cmd.shell_line(text) {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("Skipping '{}': {}", name, e);
+                return None;
+            }
+        };
+
+        match format {
+            // A bare `cd` would move the user's shell, and a subshell leaves no room for arguments
+            ExportFormat::Aliases if cmd.command.cwd.is_some() => {
+                eprintln!("Skipping '{}': it runs in its own directory, export it with --format functions", name);
+                None
+            }
+            ExportFormat::Aliases => Some(line),
+            // Only a single command line can take "$@" at its end
+            ExportFormat::Functions if text.contains('\n') || text.trim_end().ends_with(['&', ';', '|']) => Some(line),
+            ExportFormat::Functions if cmd.command.cwd.is_some() => Some(format!("({} \"$@\")", line)),
+            ExportFormat::Functions => Some(format!("{} \"$@\"", line)),
+            _ => Some(line),
+        }
+    }
+}
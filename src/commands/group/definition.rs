@@ -1,19 +1,30 @@
 use std::path::PathBuf;
 
 use clap::Subcommand;
+use clap_complete::engine::{ArgValueCandidates, ArgValueCompleter};
+use serde::Serialize;
+
+use crate::{commands::{completions::{complete_groups, complete_sebas_paths}, scope::definition::ScopeArgs}, utils::output::OutputFormat};
 
 #[derive(Subcommand)]
 pub enum GroupAction {
     /// List all groups
     #[command(alias = "lsg")]
-    List,
+    List {
+        /// Machine-readable output
+        #[arg(short, long, value_enum)]
+        format: Option<OutputFormat>,
+    },
     /// Add a new group
     #[command(alias = "addg")]
     Add {
         /// Group name
         name: String,
         /// Path of the .sebas, current if not specifid
-        path: Option<PathBuf>, 
+        #[arg(add = ArgValueCompleter::new(complete_sebas_paths), conflicts_with_all = ["scope", "level"])]
+        path: Option<PathBuf>,
+        #[command(flatten)]
+        scope: ScopeArgs,
         /// Skip confirmation prompts
         #[arg(short, long)]
         yes: bool,
@@ -22,12 +33,15 @@ pub enum GroupAction {
     #[command(alias = "mvg")]
     Move {
         /// Old group name
+        #[arg(add = ArgValueCandidates::new(complete_groups))]
         old_name: String,
-        /// location of group, current if not specifid
-        old_path: Option<PathBuf>,
         /// New group name
         new_name: String,
-        /// location of group, current if not specifid
+        /// .sebas folder holding the group, the nearest if not specified
+        #[arg(long)]
+        old_path: Option<PathBuf>,
+        /// .sebas folder to move the group into, the nearest if not specified
+        #[arg(long)]
         new_path: Option<PathBuf>,
         /// Skip confirmation prompts
         #[arg(short, long)]
@@ -37,6 +51,7 @@ pub enum GroupAction {
     #[command(alias = "rmg")]
     Remove {
         /// Group name
+        #[arg(add = ArgValueCandidates::new(complete_groups))]
         name: String,
         /// Path of the .sebas, current if not specifid
         path: Option<PathBuf>,
@@ -44,4 +59,46 @@ pub enum GroupAction {
         #[arg(short, long)]
         yes: bool,
     },
+    /// Encrypt a group with a passphrase
+    Encrypt {
+        /// Group name
+        #[arg(add = ArgValueCandidates::new(complete_groups))]
+        name: String,
+        /// Path of the .sebas, current if not specifid
+        path: Option<PathBuf>,
+        /// Key file mixed into the passphrase
+        #[arg(short, long)]
+        key_file: Option<PathBuf>,
+        /// Encrypt even though the folder history keeps the plain text
+        #[arg(long)]
+        keep_history: bool,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Store an encrypted group as plain YAML again
+    Decrypt {
+        /// Group name
+        #[arg(add = ArgValueCandidates::new(complete_groups))]
+        name: String,
+        /// Path of the .sebas, current if not specifid
+        path: Option<PathBuf>,
+        /// Key file mixed into the passphrase
+        #[arg(short, long)]
+        key_file: Option<PathBuf>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// A group as reported by `sebas group list --format`.
+#[derive(Serialize, Debug, Clone)]
+pub struct GroupSummary {
+    pub(crate) name: String,
+    pub(crate) folder_path: PathBuf,
+    /// `None` while the group is encrypted and locked
+    pub(crate) commands: Option<usize>,
+    pub(crate) encrypted: bool,
+    pub(crate) locked: bool,
 }
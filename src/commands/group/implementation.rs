@@ -1,9 +1,15 @@
-use std::{clone, fs, path::PathBuf};
+use std::{fs, path::{Path, PathBuf}};
 
-use crate::{commands::core::definition::CommandGroup, SebasApp};
+use crate::{commands::{core::definition::CommandGroup, trash::definition::{TrashEntry, TrashItem}}, utils::dir::is_group_file, SebasApp};
 
 impl SebasApp {
     pub fn load_group(&self, group_name: &str) -> Result<CommandGroup, Box<dyn std::error::Error>> {
+        let encrypted_file = self.sebas_dir.join(format!("{}.enc", group_name));
+        if encrypted_file.exists() {
+            return Self::read_encrypted_group(&encrypted_file)?
+                .ok_or_else(|| format!("Group '{}' is encrypted and locked. Run 'sebas unlock' first.", group_name).into());
+        }
+
         let group_file = self.sebas_dir.join(format!("{}.yaml", group_name));
         if !group_file.exists() {
             return Ok(CommandGroup::new());
@@ -14,18 +20,38 @@ impl SebasApp {
         Ok(group)
     }
 
-    pub fn save_group(&self, group_name: &str, group_file: PathBuf, group: &CommandGroup) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save_group(&self, _group_name: &str, group_file: PathBuf, group: &CommandGroup) -> Result<(), Box<dyn std::error::Error>> {
+        let sebas_dir = group_file.parent().unwrap_or(&self.sebas_dir).to_path_buf();
+        Self::write_sebas_dir(&sebas_dir, || Self::write_group_file(&group_file, group))
+    }
+
+    /// Write a group in place, re-encrypting it if it has an `.enc` file. Callers wrap
+    /// this in `write_sebas_dir`, `save_group` does it for a single group.
+    pub fn write_group_file(group_file: &Path, group: &CommandGroup) -> Result<(), Box<dyn std::error::Error>> {
+        let encrypted_file = group_file.with_extension("enc");
+        if encrypted_file.exists() {
+            return Self::write_encrypted_group(&encrypted_file, group);
+        }
+
         let content = serde_yaml::to_string(group)?;
-        fs::write(&group_file, content)?;
+        fs::write(group_file, content)?;
         Ok(())
     }
 
+    /// Both possible files of a group, plain and encrypted.
+    pub fn group_files(&self, group_name: &str) -> Vec<PathBuf> {
+        vec![
+            self.sebas_dir.join(format!("{}.yaml", group_name)),
+            self.sebas_dir.join(format!("{}.enc", group_name)),
+        ]
+    }
+
     pub fn get_all_groups(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let mut groups = Vec::new();
         for entry in fs::read_dir(&self.sebas_dir)? {
             let entry = entry?;
             let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
+            if is_group_file(&path) || path.extension().and_then(|s| s.to_str()) == Some("enc") {
                 if let Some(name) = path.file_stem().and_then(|s| s.to_str()) {
                     groups.push(name.to_string());
                 }
@@ -55,14 +81,16 @@ impl SebasApp {
         }
 
         let group = CommandGroup::new();
-        self.save_group(name, group_file, &group)?;
+        Self::journaled(format!("add group {}", name), vec![group_file.clone()], || self.save_group(name, group_file, &group))?;
         println!("Group '{}' created successfully.", name);
         Ok(())
     }
 
-    pub fn rename_group(&self, old_name: &str, new_name: &str, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
-        let old_file = self.sebas_dir.join(format!("{}.yaml", old_name));
-        let new_file = self.sebas_dir.join(format!("{}.yaml", new_name));
+    pub fn rename_group(&self, old_name: &str, old_path: Option<PathBuf>, new_name: &str, new_path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let old_dir = old_path.unwrap_or_else(|| self.sebas_dir.clone());
+        let extension = if old_dir.join(format!("{}.enc", old_name)).exists() { "enc" } else { "yaml" };
+        let old_file = old_dir.join(format!("{}.{}", old_name, extension));
+        let new_file = new_path.unwrap_or_else(|| self.sebas_dir.clone()).join(format!("{}.{}", new_name, extension));
         
         if !old_file.exists() {
             return Err(format!("Group '{}' not found.", old_name).into());
@@ -77,19 +105,27 @@ impl SebasApp {
             return Ok(());
         }
 
-        fs::rename(&old_file, &new_file)?;
+        let old_dir = old_file.parent().unwrap_or(&self.sebas_dir);
+        let new_dir = new_file.parent().unwrap_or(&self.sebas_dir);
+        Self::journaled(format!("move group {} to {}", old_name, new_name), vec![old_file.clone(), new_file.clone()], || {
+            Self::write_sebas_dir(old_dir, || Self::write_sebas_dir(new_dir, || Ok(fs::rename(&old_file, &new_file)?)))
+        })?;
         println!("Group '{}' renamed to '{}'.", old_name, new_name);
         Ok(())
     }
 
-    pub fn remove_group(&self, name: &str, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
-        let group_file = self.sebas_dir.join(format!("{}.yaml", name));
+    pub fn remove_group(&self, name: &str, path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let app = SebasApp { sebas_dir: path.unwrap_or_else(|| self.sebas_dir.clone()) };
+        let mut group_file = app.sebas_dir.join(format!("{}.yaml", name));
+        if !group_file.exists() {
+            group_file.set_extension("enc");
+        }
         
         if !group_file.exists() {
             return Err(format!("Group '{}' not found.", name).into());
         }
 
-        let group = self.load_group(name)?;
+        let group = app.load_group(name)?;
         let command_count = group.commands.len();
 
         if !yes && !Self::confirm(&format!("Delete group '{}' and all its {} commands?", name, command_count)) {
@@ -97,8 +133,15 @@ impl SebasApp {
             return Ok(());
         }
 
-        fs::remove_file(&group_file)?;
-        println!("Group '{}' and {} commands deleted.", name, command_count);
+        // Keep the file as it was on disk so encrypted groups stay encrypted in the trash
+        Self::expire_trash(&app.sebas_dir);
+        let file_name = group_file.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string();
+        let entry = TrashEntry::new(name, TrashItem::Group { file_name, content: fs::read_to_string(&group_file)? });
+        Self::journaled(format!("remove group {}", name), vec![group_file.clone(), entry.path(&app.sebas_dir)], || {
+            entry.write(&app.sebas_dir)?;
+            Self::write_sebas_dir(&app.sebas_dir, || Ok(fs::remove_file(&group_file)?))
+        })?;
+        println!("Group '{}' and {} commands moved to the trash.", name, command_count);
         Ok(())
     }
 
@@ -0,0 +1,40 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::{Subcommand, ValueEnum};
+
+#[derive(Subcommand)]
+pub enum ImportAction {
+    /// Import Makefile targets, just recipes, npm scripts and cargo aliases
+    Tasks {
+        /// Directory to scan (defaults to current directory)
+        path: Option<PathBuf>,
+        /// Group to import into (defaults to "Tasks")
+        #[arg(short, long)]
+        group: Option<String>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// A command discovered by an importer, before it is saved to a group.
+#[derive(Debug, Clone)]
+pub struct ImportedCommand {
+    pub(crate) command: String,
+    pub(crate) comment: Option<String>,
+    /// Group derived from the source's tags, the default group if `None`
+    pub(crate) group: Option<String>,
+    pub(crate) vars: BTreeMap<String, String>,
+    /// Directory the command runs in, relative to the project
+    pub(crate) cwd: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ImportSource {
+    /// navi `.cheat` files
+    Navi,
+    /// pet `snippet.toml`
+    Pet,
+    /// tldr markdown pages
+    Tldr,
+}
@@ -0,0 +1,114 @@
+use std::{collections::{BTreeMap, HashSet}, env, path::{Path, PathBuf}};
+
+use crate::{commands::{core::definition::SavedCommand, import::definition::{ImportSource, ImportedCommand}}, utils::{cheatsheets::read_cheatsheets, tasks::discover_tasks}, SebasApp};
+
+impl SebasApp {
+    pub fn import_tasks(&self, path: Option<PathBuf>, group: Option<String>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = path.unwrap_or(env::current_dir()?).canonicalize()?;
+        let mut tasks = discover_tasks(&dir);
+
+        if tasks.is_empty() {
+            println!("No tasks found in {}.", dir.display());
+            return Ok(());
+        }
+
+        // Tasks outside the folder owning .sebas run in their own directory
+        let project_root = self.sebas_dir.parent().and_then(|p| p.canonicalize().ok());
+        if project_root.as_deref() != Some(dir.as_path()) {
+            let location = project_root
+                .and_then(|root| dir.strip_prefix(root).ok().map(|p| p.to_path_buf()))
+                .unwrap_or_else(|| dir.clone());
+            for task in &mut tasks {
+                task.cwd = Some(location.to_string_lossy().into_owned());
+            }
+        }
+
+        let group_name = group.unwrap_or_else(|| "Tasks".to_string());
+        self.import_commands(&group_name, tasks, yes)
+    }
+
+    pub fn import_cheatsheets(&self, source: ImportSource, path: &Path, group: Option<String>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let commands = read_cheatsheets(source, path, group.as_deref())?;
+
+        if commands.is_empty() {
+            println!("No snippets found in {}.", path.display());
+            return Ok(());
+        }
+
+        self.import_commands("Imported", commands, yes)
+    }
+
+    pub fn import_commands(&self, default_group: &str, commands: Vec<ImportedCommand>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let total = commands.len();
+        let mut by_group: BTreeMap<String, Vec<ImportedCommand>> = BTreeMap::new();
+        for cmd in commands {
+            let group = cmd.group.clone().unwrap_or_else(|| default_group.to_string());
+            by_group.entry(group).or_default().push(cmd);
+        }
+        // Skip anything already stored in the target group
+        // Skip anything whose hash is already stored in the target group
+        let mut pending = Vec::new();
+        for (group_name, imported) in by_group {
+            let group_data = self.load_group(&group_name)?;
+            // `make build` of two subprojects share a hash, the directory tells them apart
+            let mut known: HashSet<(String, Option<String>)> = group_data.commands.iter().map(|cmd| (cmd.hash.clone(), cmd.cwd.clone())).collect();
+            let mut new_commands = Vec::new();
+            for imported in imported {
+                // Cheatsheets and task files can hold tokens just like typed commands,
+                // task comments show the recipe itself
+                let command = Self::redact_secrets(imported.command, yes)?;
+                let hash = Self::generate_hash(&command);
+                if !known.insert((hash.clone(), imported.cwd.clone())) {
+                    continue;
+                }
+                new_commands.push(SavedCommand {
+                    command,
+                    name: None,
+                    comment: imported.comment.map(|comment| Self::redact_secrets(comment, yes)).transpose()?,
+                    hash,
+                    created_at: chrono::Utc::now().to_rfc3339(),
+                    when: None,
+                    vars: imported.vars,
+                    cwd: imported.cwd,
+                    env: Default::default(),
+                    variants: Default::default(),
+                    danger: false,
+                });
+            }
+            if !new_commands.is_empty() {
+                pending.push((group_name, group_data, new_commands));
+            }
+        }
+
+        let imported: usize = pending.iter().map(|(_, _, new)| new.len()).sum();
+        if imported == 0 {
+            println!("All {} commands are already saved.", total);
+            return Ok(());
+        }
+
+        for (group_name, _, new_commands) in &pending {
+            println!("{} ({} new)", group_name, new_commands.len());
+            for cmd in new_commands {
+                let location = cmd.cwd.as_ref().map(|cwd| format!(" (in {})", cwd)).unwrap_or_default();
+                match &cmd.comment {
+                    Some(comment) => println!("  + {}{}  # {}", cmd.command, location, comment),
+                    None => println!("  + {}{}", cmd.command, location),
+                }
+            }
+        }
+
+        if !yes && !Self::confirm(&format!("Import {} new commands into {} groups?", imported, pending.len())) {
+            println!("Import cancelled.");
+            return Ok(());
+        }
+
+        for (group_name, mut group_data, new_commands) in pending {
+            group_data.commands.extend(new_commands);
+            let group_file = self.sebas_dir.join(format!("{}.yaml", group_name));
+            self.save_group(&group_name, group_file, &group_data)?;
+        }
+
+        println!("Imported {} commands ({} already present).", imported, total - imported);
+        Ok(())
+    }
+}
@@ -1,20 +1,31 @@
 use std::{env, fs, path::PathBuf};
 
-use crate::SebasApp;
+use crate::{commands::trust::definition::TrustStore, SebasApp};
 
 impl SebasApp {
 
-    pub fn init_folder(path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn init_folder(path: Option<PathBuf>, git: bool) -> Result<(), Box<dyn std::error::Error>> {
         let target_path = path.unwrap_or_else(|| env::current_dir().unwrap());
         let sebas_dir = target_path.join(".sebas");
         
         if sebas_dir.exists() {
+            if git {
+                return Self::enable_history(&sebas_dir);
+            }
             println!("SEBAS folder already exists at: {}", sebas_dir.display());
             return Ok(());
         }
 
         fs::create_dir_all(&sebas_dir)?;
+
+        // A folder created here is the user's own
+        let mut trust = TrustStore::load();
+        trust.trust(&sebas_dir);
+        trust.save()?;
         println!("SEBAS folder initialized at: {}", sebas_dir.display());
+        if git {
+            Self::enable_history(&sebas_dir)?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,11 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Keys derived by `sebas unlock`, cached in the runtime dir until they expire.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Session {
+    pub(crate) expires_at: String,
+    /// Derived key per Argon2 salt, both base64
+    pub(crate) keys: BTreeMap<String, String>,
+}
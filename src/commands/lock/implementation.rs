@@ -0,0 +1,238 @@
+use std::{collections::BTreeMap, env, fs, io::{self, Write}, path::{Path, PathBuf}};
+
+use chrono::{DateTime, Duration, Utc};
+use termion::input::TermRead;
+
+use crate::{commands::{core::definition::CommandGroup, lock::definition::Session}, utils::{crypto::{self, Envelope, KEY_LEN}, dir::{get_all_sebas_dirs, get_runtime_dir, write_private}, shell::quote}, SebasApp};
+
+const DEFAULT_SESSION_MINUTES: i64 = 15;
+
+impl Session {
+    pub fn path() -> Option<PathBuf> {
+        get_runtime_dir().map(|dir| dir.join("session.yaml"))
+    }
+
+    /// The current session, or an empty one if it expired.
+    pub fn load() -> Self {
+        let session: Self = Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default();
+        if session.is_valid() { session } else { Self::default() }
+    }
+
+    fn is_valid(&self) -> bool {
+        DateTime::parse_from_rfc3339(&self.expires_at).is_ok_and(|expiry| expiry > Utc::now())
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not determine the sebas runtime directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn key_for(&self, salt: &str) -> Option<[u8; KEY_LEN]> {
+        self.keys.get(salt).and_then(|key| crypto::decode_key(key))
+    }
+
+    fn insert(&mut self, salt: &str, key: &[u8; KEY_LEN]) {
+        if !self.is_valid() {
+            self.expires_at = (Utc::now() + Duration::minutes(DEFAULT_SESSION_MINUTES)).to_rfc3339();
+        }
+        self.keys.insert(salt.to_string(), crypto::encode_key(key));
+    }
+}
+
+impl SebasApp {
+    fn read_passphrase(prompt: &str) -> Result<String, Box<dyn std::error::Error>> {
+        if let Ok(passphrase) = env::var("SEBAS_PASSPHRASE") {
+            return Ok(passphrase);
+        }
+        print!("{}", prompt);
+        io::stdout().flush()?;
+        let passphrase = io::stdin()
+            .read_passwd(&mut io::stdout())?
+            .ok_or("No passphrase entered")?;
+        println!();
+        Ok(passphrase)
+    }
+
+    fn key_file_from(key_file: Option<PathBuf>) -> Option<PathBuf> {
+        key_file.or_else(|| env::var_os("SEBAS_KEY_FILE").map(PathBuf::from))
+    }
+
+    fn encrypted_files(dir: &Path) -> Vec<PathBuf> {
+        fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("enc"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Decrypt a group with the cached session key, `None` if it is locked.
+    pub fn read_encrypted_group(path: &Path) -> Result<Option<CommandGroup>, Box<dyn std::error::Error>> {
+        let envelope = Envelope::read(path)?;
+        let Some(key) = Session::load().key_for(&envelope.salt) else {
+            return Ok(None);
+        };
+        let content = crypto::open(&envelope, &key)?;
+        Ok(Some(serde_yaml::from_str(&content)?))
+    }
+
+    pub fn write_encrypted_group(path: &Path, group: &CommandGroup) -> Result<(), Box<dyn std::error::Error>> {
+        let envelope = Envelope::read(path)?;
+        let key = Session::load()
+            .key_for(&envelope.salt)
+            .ok_or("Group is encrypted and locked. Run 'sebas unlock' first.")?;
+        let sealed = crypto::seal(&serde_yaml::to_string(group)?, &key, &envelope.salt, envelope.key_file)?;
+        fs::write(path, serde_yaml::to_string(&sealed)?)?;
+        Ok(())
+    }
+
+    pub fn unlock(&self, key_file: Option<PathBuf>, timeout: i64) -> Result<(), Box<dyn std::error::Error>> {
+        // One sample file per salt is enough to verify the passphrase
+        let mut samples: BTreeMap<String, (PathBuf, Envelope)> = BTreeMap::new();
+        for dir in get_all_sebas_dirs() {
+            for path in Self::encrypted_files(&dir) {
+                let envelope = Envelope::read(&path)?;
+                samples.entry(envelope.salt.clone()).or_insert((path, envelope));
+            }
+        }
+
+        if samples.is_empty() {
+            println!("No encrypted groups found.");
+            return Ok(());
+        }
+
+        let key_file = Self::key_file_from(key_file);
+        let passphrase = Self::read_passphrase("Passphrase: ")?;
+        let mut session = Session::load();
+        for (salt, (path, envelope)) in &samples {
+            if envelope.key_file && key_file.is_none() {
+                return Err(format!("{} needs a key file, pass --key-file.", path.display()).into());
+            }
+            let key_file = key_file.as_deref().filter(|_| envelope.key_file);
+            let key = crypto::derive_key(&passphrase, key_file, salt)?;
+            crypto::open(envelope, &key).map_err(|e| format!("{}: {}", path.display(), e))?;
+            session.keys.insert(salt.clone(), crypto::encode_key(&key));
+        }
+
+        session.expires_at = (Utc::now() + Duration::minutes(timeout)).to_rfc3339();
+        session.save()?;
+        println!("Unlocked encrypted groups for {} minutes.", timeout);
+        Ok(())
+    }
+
+    pub fn lock() -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(path) = Session::path().filter(|path| path.exists()) {
+            fs::remove_file(path)?;
+        }
+        println!("Encrypted groups locked.");
+        Ok(())
+    }
+
+    pub fn encrypt_group(&self, name: &str, path: Option<PathBuf>, key_file: Option<PathBuf>, keep_history: bool, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = path.unwrap_or_else(|| self.sebas_dir.clone());
+        let plain_file = dir.join(format!("{}.yaml", name));
+        let encrypted_file = dir.join(format!("{}.enc", name));
+
+        if encrypted_file.exists() {
+            return Err(format!("Group '{}' is already encrypted.", name).into());
+        }
+        if !plain_file.exists() {
+            return Err(format!("Group '{}' not found.", name).into());
+        }
+        // Encrypting only protects the file from now on, earlier commits keep the plain text
+        if dir.join(".git").is_dir() && !keep_history {
+            return Err(format!(
+                "{} has a history that keeps '{}.yaml' in plain text. Pass --keep-history to encrypt anyway, \
+                 then purge it with 'git -C {} filter-repo --invert-paths --path {}.yaml' or remove {} to start a fresh history.",
+                dir.display(),
+                name,
+                quote(&dir.to_string_lossy()),
+                name,
+                dir.join(".git").display()
+            )
+            .into());
+        }
+
+        if !yes && !Self::confirm(&format!("Encrypt group '{}'?", name)) {
+            println!("Encryption cancelled.");
+            return Ok(());
+        }
+
+        // Share the salt with other encrypted groups of the folder so one unlock covers them
+        let key_file = Self::key_file_from(key_file);
+        let existing = Self::encrypted_files(&dir)
+            .into_iter()
+            .filter_map(|path| Envelope::read(&path).ok())
+            .find(|envelope| envelope.key_file == key_file.is_some());
+        let mut session = Session::load();
+        let (salt, key) = match existing {
+            Some(envelope) => {
+                let key = match session.key_for(&envelope.salt) {
+                    Some(key) => key,
+                    None => crypto::derive_key(&Self::read_passphrase("Passphrase: ")?, key_file.as_deref(), &envelope.salt)?,
+                };
+                crypto::open(&envelope, &key)?;
+                (envelope.salt, key)
+            }
+            None => {
+                let passphrase = Self::read_passphrase("New passphrase: ")?;
+                if env::var("SEBAS_PASSPHRASE").is_err() && Self::read_passphrase("Repeat passphrase: ")? != passphrase {
+                    return Err("Passphrases do not match.".into());
+                }
+                let salt = crypto::random_salt();
+                let key = crypto::derive_key(&passphrase, key_file.as_deref(), &salt)?;
+                (salt, key)
+            }
+        };
+
+        let sealed = crypto::seal(&fs::read_to_string(&plain_file)?, &key, &salt, key_file.is_some())?;
+        Self::write_sebas_dir(&dir, || {
+            fs::write(&encrypted_file, serde_yaml::to_string(&sealed)?)?;
+            Ok(fs::remove_file(&plain_file)?)
+        })?;
+
+        session.insert(&salt, &key);
+        session.save()?;
+        println!("Group '{}' encrypted.", name);
+        Ok(())
+    }
+
+    pub fn decrypt_group(&self, name: &str, path: Option<PathBuf>, key_file: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = path.unwrap_or_else(|| self.sebas_dir.clone());
+        let plain_file = dir.join(format!("{}.yaml", name));
+        let encrypted_file = dir.join(format!("{}.enc", name));
+
+        if !encrypted_file.exists() {
+            return Err(format!("Group '{}' is not encrypted.", name).into());
+        }
+
+        if !yes && !Self::confirm(&format!("Store group '{}' unencrypted?", name)) {
+            println!("Decryption cancelled.");
+            return Ok(());
+        }
+
+        let envelope = Envelope::read(&encrypted_file)?;
+        let key = match Session::load().key_for(&envelope.salt) {
+            Some(key) => key,
+            None => {
+                let key_file = Self::key_file_from(key_file).filter(|_| envelope.key_file);
+                crypto::derive_key(&Self::read_passphrase("Passphrase: ")?, key_file.as_deref(), &envelope.salt)?
+            }
+        };
+
+        let content = crypto::open(&envelope, &key)?;
+        Self::write_sebas_dir(&dir, || {
+            fs::write(&plain_file, content)?;
+            Ok(fs::remove_file(&encrypted_file)?)
+        })?;
+        println!("Group '{}' decrypted.", name);
+        Ok(())
+    }
+}
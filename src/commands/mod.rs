@@ -1,5 +1,24 @@
 pub mod group;
 pub mod core;
+pub mod capture;
+pub mod completions;
 pub mod sync;
+pub mod targets;
 pub mod init;
+pub mod trust;
+pub mod lock;
+pub mod pack;
+pub mod pick;
+pub mod profile;
+pub mod runs;
+pub mod scope;
+pub mod editor;
+pub mod export;
+pub mod import;
+pub mod trash;
+pub mod undo;
+pub mod usage;
+pub mod versioning;
+pub mod wizard;
+#[allow(clippy::module_inception)]
 pub mod commands;
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::core::definition::CommandGroup;
+
+#[derive(Subcommand)]
+pub enum PackAction {
+    /// Create a signing key pair for publishing packs
+    Keygen {
+        /// Secret key file, the public key is written next to it with a .pub extension
+        output: PathBuf,
+    },
+    /// Bundle groups of the nearest .sebas folder into a signed pack
+    Build {
+        /// Groups to include
+        #[arg(required = true)]
+        groups: Vec<String>,
+        /// Secret key created by 'sebas pack keygen'
+        #[arg(short, long)]
+        key: PathBuf,
+        /// Pack file to write
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Pack name (defaults to the output file name)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+    /// Check the signature and command hashes of a pack
+    Verify {
+        /// Pack file
+        file: PathBuf,
+        /// Expected publisher key, base64 or a .pub file
+        #[arg(short, long)]
+        signer: Option<String>,
+    },
+    /// Verify a pack and merge its groups into a .sebas folder
+    Install {
+        /// Pack file
+        file: PathBuf,
+        /// Expected publisher key, base64 or a .pub file
+        #[arg(short, long)]
+        signer: Option<String>,
+        /// Target .sebas folder (defaults to the nearest one)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// A signed, single-file bundle of command groups.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pack {
+    pub(crate) manifest: PackManifest,
+    /// Ed25519 signature over the JSON encoded manifest, base64
+    pub(crate) signature: String,
+    pub(crate) groups: Vec<PackGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackManifest {
+    pub(crate) name: String,
+    pub(crate) created_at: String,
+    /// Publisher's Ed25519 public key, base64
+    pub(crate) public_key: String,
+    pub(crate) commands: Vec<PackEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackEntry {
+    pub(crate) group: String,
+    pub(crate) hash: String,
+    /// SHA-256 of the JSON encoded command, hex
+    pub(crate) sha256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackGroup {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) group: CommandGroup,
+}
@@ -0,0 +1,219 @@
+use std::{collections::{BTreeMap, HashSet}, fs, io::Write, path::{Path, PathBuf}};
+
+use colored::Colorize;
+use sha2::{Digest, Sha256};
+
+use crate::{commands::{core::definition::SavedCommand, pack::definition::{Pack, PackEntry, PackGroup, PackManifest}}, utils::{crypto, dir::{create_private_file, is_valid_group_name}, secrets}, SebasApp};
+
+impl Pack {
+    pub fn read(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        serde_yaml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| format!("{} is not a sebas pack: {}", path.display(), e).into())
+    }
+
+    pub fn command_digest(command: &SavedCommand) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(format!("{:x}", Sha256::digest(serde_json::to_vec(command)?)))
+    }
+
+    /// Check the signature, the optional pinned publisher and every command against the manifest.
+    pub fn verify(&self, signer: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(signer) = signer {
+            if Self::signer_key(signer)? != self.manifest.public_key {
+                return Err(format!("Pack is signed by {}, not the expected publisher.", self.manifest.public_key).into());
+            }
+        }
+        crypto::verify_signature(&serde_json::to_vec(&self.manifest)?, &self.signature, &self.manifest.public_key)?;
+        // Group names become file names in the target folder
+        if let Some(group) = self.groups.iter().find(|group| !is_valid_group_name(&group.name)) {
+            return Err(format!("Invalid group name '{}' in the pack.", group.name).into());
+        }
+
+        let mut expected: BTreeMap<(&str, &str), &str> = self
+            .manifest
+            .commands
+            .iter()
+            .map(|entry| ((entry.group.as_str(), entry.hash.as_str()), entry.sha256.as_str()))
+            .collect();
+        for group in &self.groups {
+            for cmd in &group.group.commands {
+                let digest = Self::command_digest(cmd)?;
+                match expected.remove(&(group.name.as_str(), cmd.hash.as_str())) {
+                    Some(sha256) if sha256 == digest => {}
+                    Some(_) => return Err(format!("Command '{}' in group '{}' was modified.", cmd.command, group.name).into()),
+                    None => return Err(format!("Command '{}' in group '{}' is not in the manifest.", cmd.command, group.name).into()),
+                }
+            }
+        }
+        if let Some(((group, hash), _)) = expected.into_iter().next() {
+            return Err(format!("Command {} of group '{}' is missing from the pack.", hash, group).into());
+        }
+        Ok(())
+    }
+
+    // Accept the key itself or the .pub file written by keygen
+    fn signer_key(signer: &str) -> Result<String, Box<dyn std::error::Error>> {
+        let path = Path::new(signer);
+        if path.is_file() {
+            Ok(fs::read_to_string(path)?.trim().to_string())
+        } else {
+            Ok(signer.to_string())
+        }
+    }
+
+    fn command_count(&self) -> usize {
+        self.groups.iter().map(|group| group.group.commands.len()).sum()
+    }
+}
+
+impl SebasApp {
+    pub fn pack_keygen(output: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let public_file = output.with_extension("pub");
+        if output.exists() || public_file.exists() {
+            return Err(format!("{} already exists.", output.display()).into());
+        }
+
+        let key = crypto::generate_signing_key();
+        create_private_file(output)?.write_all(crypto::encode_key(&key.to_bytes()).as_bytes())?;
+        let public_key = crypto::encode_key(&key.verifying_key().to_bytes());
+        fs::write(&public_file, format!("{}\n", public_key))?;
+
+        println!("Secret key written to {}. Keep it private.", output.display());
+        println!("Public key written to {}:", public_file.display());
+        println!("  {}", public_key);
+        Ok(())
+    }
+
+    pub fn pack_build(&self, groups: Vec<String>, key: &Path, output: &Path, name: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let key = crypto::read_signing_key(key)?;
+        let all_groups = self.get_all_groups()?;
+
+        let mut packed = Vec::new();
+        let mut entries = Vec::new();
+        for group_name in groups {
+            if !all_groups.contains(&group_name) {
+                return Err(format!("Group '{}' not found.", group_name).into());
+            }
+            let group = self.load_group(&group_name)?;
+            for cmd in &group.commands {
+                entries.push(PackEntry {
+                    group: group_name.clone(),
+                    hash: cmd.hash.clone(),
+                    sha256: Pack::command_digest(cmd)?,
+                });
+            }
+            packed.push(PackGroup { name: group_name, group });
+        }
+
+        let name = name
+            .or_else(|| output.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+            .unwrap_or_else(|| "pack".to_string());
+        let manifest = PackManifest {
+            name,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            public_key: crypto::encode_key(&key.verifying_key().to_bytes()),
+            commands: entries,
+        };
+        let pack = Pack {
+            signature: crypto::sign(&serde_json::to_vec(&manifest)?, &key),
+            manifest,
+            groups: packed,
+        };
+
+        fs::write(output, serde_yaml::to_string(&pack)?)?;
+        println!("Pack '{}' with {} commands written to {}.", pack.manifest.name, pack.command_count(), output.display());
+        Ok(())
+    }
+
+    fn load_verified_pack(file: &Path, signer: Option<&str>) -> Result<Pack, Box<dyn std::error::Error>> {
+        let pack = Pack::read(file)?;
+        pack.verify(signer).map_err(|e| format!("Verification of {} failed: {}", file.display(), e))?;
+        Ok(pack)
+    }
+
+    pub fn pack_verify(&self, file: &Path, signer: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let pack = Self::load_verified_pack(file, signer.as_deref())?;
+        println!("{} {}", "Pack:".bold(), pack.manifest.name);
+        println!("{} {}", "Signed by:".bold(), pack.manifest.public_key);
+        println!("{} {}", "Fingerprint:".bold(), crypto::fingerprint(&pack.manifest.public_key));
+        println!("{} {}", "Created:".bold(), pack.manifest.created_at);
+        for group in &pack.groups {
+            println!("  {} ({} commands)", group.name, group.group.commands.len());
+        }
+        println!("{}", "Signature and command hashes are valid.".green());
+        if signer.is_none() {
+            println!("{}", "The publisher was not checked, pass --signer to pin their key.".yellow());
+        }
+        Ok(())
+    }
+
+    pub fn pack_install(&self, file: &Path, signer: Option<String>, path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if yes && signer.is_none() {
+            return Err("Pass --signer to install a pack without confirmation.".into());
+        }
+        let pack = Self::load_verified_pack(file, signer.as_deref())?;
+        let app = SebasApp { sebas_dir: Self::resolve_sebas_path(path, &self.sebas_dir) };
+        if !app.sebas_dir.is_dir() {
+            return Err(format!("No .sebas folder at {}", app.sebas_dir.display()).into());
+        }
+
+        println!("Pack '{}' signed by {}", pack.manifest.name, pack.manifest.public_key);
+        if signer.is_none() {
+            println!("{}", "Warning: the publisher was not checked, pass --signer to pin their key.".yellow());
+            println!("Key fingerprint: {}", crypto::fingerprint(&pack.manifest.public_key).bold());
+            if !Self::confirm("Do you trust the publisher with this fingerprint?") {
+                println!("Install cancelled.");
+                return Ok(());
+            }
+        }
+
+        // Skip commands already stored in the target group. The hash is recomputed, the
+        // publisher's could be wrong and would let a duplicate or a clash in
+        let total = pack.command_count();
+        let mut pending = Vec::new();
+        for packed in pack.groups {
+            let group_data = app.load_group(&packed.name)?;
+            let mut known: HashSet<String> = group_data.commands.iter().map(|cmd| cmd.hash.clone()).collect();
+            let mut new_commands: Vec<SavedCommand> = Vec::new();
+            for mut cmd in packed.group.commands {
+                cmd.hash = Self::generate_hash(&cmd.command);
+                if !known.contains(&cmd.hash) && !secrets::scan(&cmd.command).is_empty() {
+                    cmd.command = Self::redact_secrets(cmd.command, yes)?;
+                    cmd.hash = Self::generate_hash(&cmd.command);
+                }
+                if known.insert(cmd.hash.clone()) {
+                    new_commands.push(cmd);
+                }
+            }
+            if !new_commands.is_empty() {
+                pending.push((packed.name, group_data, new_commands));
+            }
+        }
+
+        let installed: usize = pending.iter().map(|(_, _, new)| new.len()).sum();
+        if installed == 0 {
+            println!("All {} commands are already installed.", total);
+            return Ok(());
+        }
+
+        for (group_name, _, new_commands) in &pending {
+            println!("{} ({} new)", group_name, new_commands.len());
+            for cmd in new_commands {
+                println!("  + {}", cmd.command);
+            }
+        }
+
+        if !yes && !Self::confirm(&format!("Install {} commands into {}?", installed, app.sebas_dir.display())) {
+            println!("Install cancelled.");
+            return Ok(());
+        }
+
+        for (group_name, mut group_data, new_commands) in pending {
+            group_data.commands.extend(new_commands);
+            let group_file = app.sebas_dir.join(format!("{}.yaml", group_name));
+            app.save_group(&group_name, group_file, &group_data)?;
+        }
+
+        println!("Installed {} commands ({} already present).", installed, total - installed);
+        Ok(())
+    }
+}
@@ -0,0 +1,7 @@
+use std::{fs::File, io::BufReader};
+
+/// The controlling terminal, where prompts go while stdout carries the result.
+pub struct Tty {
+    pub(crate) out: File,
+    pub(crate) input: BufReader<File>,
+}
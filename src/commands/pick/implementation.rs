@@ -0,0 +1,111 @@
+use std::{collections::BTreeMap, fs::OpenOptions, io::{BufRead, BufReader, Write}, process::{Command as ProcessCommand, Stdio}};
+
+use anyhow::Context;
+
+use crate::{commands::{core::definition::ResolvedCommand, pick::definition::Tty}, utils::{danger::DangerChecker, dir::get_all_sebas_dirs, placeholders::{expand, placeholders}}, SebasApp};
+
+impl Tty {
+    fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        let out = OpenOptions::new().read(true).write(true).open("/dev/tty").context("No terminal to ask on")?;
+        let input = BufReader::new(out.try_clone()?);
+        Ok(Self { out, input })
+    }
+
+    // Open the terminal on first use, so a pick that asks nothing works without one
+    fn get(slot: &mut Option<Self>) -> Result<&mut Self, Box<dyn std::error::Error>> {
+        if slot.is_none() {
+            *slot = Some(Self::open()?);
+        }
+        Ok(slot.as_mut().expect("terminal opened above"))
+    }
+
+    // `None` on end of input
+    fn prompt(&mut self, label: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        write!(self.out, "{}", label)?;
+        self.out.flush()?;
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+// Offer the lines printed by `source` in fzf, a typed query is accepted as well.
+// `None` when the user cancelled.
+fn choose_value(name: &str, source: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let candidates = ProcessCommand::new("bash")
+        .arg("-c")
+        .arg(source)
+        .stderr(Stdio::null())
+        .output()?
+        .stdout;
+
+    let mut fzf = ProcessCommand::new("fzf")
+        .arg("--height=40%")
+        .arg("--layout=reverse")
+        .arg("--print-query")
+        .arg(format!("--prompt={} › ", name))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to start fzf - make sure fzf is installed")?;
+    if let Some(mut stdin) = fzf.stdin.take() {
+        stdin.write_all(&candidates)?;
+    }
+    let output = fzf.wait_with_output()?;
+    // 1 means nothing matched the query, which is then used as typed
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        return Ok(None);
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines = text.lines();
+    let query = lines.next().unwrap_or_default();
+    Ok(Some(lines.next().unwrap_or(query).to_string()))
+}
+
+impl SebasApp {
+    fn fill_placeholders(command: &ResolvedCommand, tty: &mut Option<Tty>) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let mut values = BTreeMap::new();
+        for name in placeholders(&command.command.command) {
+            let value = match command.command.vars.get(&name) {
+                Some(source) => choose_value(&name, source)?,
+                None => Tty::get(tty)?.prompt(&format!("{}: ", name))?,
+            };
+            let Some(value) = value else {
+                return Ok(None);
+            };
+            values.insert(name, value);
+        }
+        Ok(Some(expand(&command.command.command, &values)))
+    }
+
+    /// Print only the chosen command, with placeholders filled in, on stdout. Every
+    /// prompt goes to the terminal so the result composes as `$(sebas pick)`.
+    pub fn pick_command(&self, identifier: Option<String>, relevant: bool, profile: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let command = self.select_command(identifier, relevant, profile)?;
+        Self::ensure_trusted(&command)?;
+
+        let mut tty = None;
+        let expanded = Self::fill_placeholders(&command, &mut tty)?.ok_or("Cancelled.")?;
+
+        // Checked once filled in, a value like `/` can be what makes it dangerous
+        let mut filled = command.clone();
+        filled.command.command = expanded;
+        let matches = DangerChecker::for_dirs(&get_all_sebas_dirs()).check(&filled.command);
+        if !matches.is_empty() {
+            let tty = Tty::get(&mut tty)?;
+            if !Self::confirm_danger_on(&filled, &matches, &mut tty.out, &mut tty.input)? {
+                return Err("Aborted.".into());
+            }
+        }
+
+        let line = filled.shell_line(&filled.command.command)?;
+        if let Err(e) = self.record_usage(&command) {
+            eprintln!("Warning: failed to record usage: {}", e);
+        }
+        println!("{}", line);
+        Ok(())
+    }
+}
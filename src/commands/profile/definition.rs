@@ -0,0 +1,4 @@
+use std::collections::BTreeMap;
+
+/// Variables of one profile, `.sebas/profiles/<name>.yaml` is a flat map of them.
+pub type ProfileVars = BTreeMap<String, String>;
@@ -0,0 +1,51 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::{commands::{core::definition::ResolvedCommand, profile::definition::ProfileVars}, utils::{dir::get_all_sebas_dirs, placeholders::substitute, shell::is_valid_identifier}, SebasApp};
+
+impl SebasApp {
+    fn profile_path(sebas_dir: &Path, name: &str) -> PathBuf {
+        sebas_dir.join("profiles").join(format!("{}.yaml", name))
+    }
+
+    /// Variables of profile `name` in `sebas_dir`, empty when the folder does not define it.
+    pub fn load_profile(sebas_dir: &Path, name: &str) -> Result<ProfileVars, Box<dyn std::error::Error>> {
+        let path = Self::profile_path(sebas_dir, name);
+        if !path.exists() {
+            return Ok(ProfileVars::new());
+        }
+        let vars = serde_yaml::from_str::<Option<ProfileVars>>(&fs::read_to_string(&path)?)
+            .map_err(|e| format!("Invalid profile {}: {}", path.display(), e))?;
+        Ok(vars.unwrap_or_default())
+    }
+
+    /// Fill `${var}` in the command, its directory and environment from profile `name`.
+    /// Each variable comes from the nearest definition, starting at the folder the command
+    /// belongs to and going up the chain.
+    pub fn apply_profile(command: &mut ResolvedCommand, name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(name) = name.filter(|name| !name.is_empty()) else {
+            return Ok(());
+        };
+        let dirs = get_all_sebas_dirs();
+        // A typo must not silently run against the wrong environment
+        if !is_valid_identifier(name) || !dirs.iter().any(|dir| Self::profile_path(dir, name).exists()) {
+            return Err(format!("Profile '{}' not found in any .sebas/profiles folder.", name).into());
+        }
+
+        let start = dirs.iter().position(|dir| *dir == command.folder_path).unwrap_or(dirs.len());
+        let mut vars = Self::load_profile(&command.folder_path, name)?;
+        for dir in &dirs[start..] {
+            for (var, value) in Self::load_profile(dir, name)? {
+                vars.entry(var).or_insert(value);
+            }
+        }
+
+        let saved = &mut command.command;
+        saved.command = substitute(&saved.command, &vars);
+        saved.cwd = saved.cwd.as_deref().map(|cwd| substitute(cwd, &vars));
+        for value in saved.env.values_mut() {
+            *value = substitute(value, &vars);
+        }
+        command.profile = Some(name.to_string());
+        Ok(())
+    }
+}
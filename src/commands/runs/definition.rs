@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Past `sebas run --capture` executions of one command, stored per user as
+/// `runs/<hash>.yaml` in the state dir.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RunLog {
+    /// Oldest first
+    pub(crate) runs: Vec<RunRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunRecord {
+    pub(crate) started_at: String,
+    pub(crate) duration_ms: u64,
+    /// `None` when the command was killed by a signal
+    pub(crate) status: Option<i32>,
+    /// The text that ran, after variants and profiles were applied
+    pub(crate) command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) profile: Option<String>,
+    pub(crate) stdout: String,
+    pub(crate) stderr: String,
+    /// Whether the start of the output was dropped to stay under the size cap
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) truncated: bool,
+}
+
+/// A run as listed by `sebas runs`, output left out.
+#[derive(Serialize, Debug, Clone)]
+pub struct RunSummary {
+    /// 1 is the latest run
+    pub(crate) run: usize,
+    pub(crate) started_at: String,
+    pub(crate) duration_ms: u64,
+    pub(crate) status: Option<i32>,
+    pub(crate) command: String,
+}
@@ -0,0 +1,184 @@
+use std::{fs, io::{self, Read, Write}, path::PathBuf, process::{Command as ProcessCommand, ExitStatus, Stdio}, thread, time::Instant};
+
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+
+use crate::{commands::{core::definition::ResolvedCommand, runs::definition::{RunLog, RunRecord, RunSummary}}, utils::{dir::{get_state_dir, write_private}, output::{render, OutputFormat}}, SebasApp};
+
+// Bytes kept of each stream per run, the end of the output matters most
+const CAPTURE_LIMIT: usize = 64 * 1024;
+const KEPT_RUNS: usize = 20;
+const RUN_COLUMNS: &[&str] = &["run", "started_at", "duration_ms", "status", "command"];
+
+// Copy `source` to `sink` as it arrives, keeping the last CAPTURE_LIMIT bytes
+fn tee(mut source: impl Read, mut sink: impl Write) -> (Vec<u8>, bool) {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut buffer = [0u8; 8192];
+    while let Ok(read) = source.read(&mut buffer) {
+        if read == 0 {
+            break;
+        }
+        let _ = sink.write_all(&buffer[..read]);
+        let _ = sink.flush();
+        kept.extend_from_slice(&buffer[..read]);
+        if kept.len() > CAPTURE_LIMIT {
+            kept.drain(..kept.len() - CAPTURE_LIMIT);
+            truncated = true;
+        }
+    }
+    (kept, truncated)
+}
+
+// Ignores SIGINT in sebas until dropped, the previous handler is put back
+struct IgnoreInterrupt(libc::sighandler_t);
+
+impl IgnoreInterrupt {
+    fn new() -> Self {
+        // SAFETY: SIG_IGN installs no handler code, it only changes the disposition
+        Self(unsafe { libc::signal(libc::SIGINT, libc::SIG_IGN) })
+    }
+}
+
+impl Drop for IgnoreInterrupt {
+    fn drop(&mut self) {
+        // SAFETY: restores the disposition returned by `signal` above
+        unsafe { libc::signal(libc::SIGINT, self.0) };
+    }
+}
+
+fn local_time(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
+
+impl RunLog {
+    pub fn path(hash: &str) -> Option<PathBuf> {
+        get_state_dir().map(|dir| dir.join("runs").join(format!("{}.yaml", hash)))
+    }
+
+    pub fn load(hash: &str) -> Self {
+        Self::path(hash)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, hash: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path(hash).ok_or("Could not determine the sebas state directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, run: RunRecord) {
+        self.runs.push(run);
+        if self.runs.len() > KEPT_RUNS {
+            self.runs.drain(..self.runs.len() - KEPT_RUNS);
+        }
+    }
+}
+
+impl SebasApp {
+    /// Run `process` with its output shown live and recorded in the run log of `command`.
+    pub fn run_captured(command: &ResolvedCommand, mut process: ProcessCommand) -> Result<ExitStatus, Box<dyn std::error::Error>> {
+        let started_at = Utc::now().to_rfc3339();
+        let start = Instant::now();
+        let mut child = process.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+
+        let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+        let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+        let stdout = thread::spawn(move || tee(stdout, io::stdout()));
+        let stderr = thread::spawn(move || tee(stderr, io::stderr()));
+        // Ctrl-C goes to the child, sebas stays to record how it ended
+        let status = {
+            let _ignored = IgnoreInterrupt::new();
+            child.wait()?
+        };
+        let (stdout, stdout_truncated) = stdout.join().map_err(|_| "Failed to capture stdout")?;
+        let (stderr, stderr_truncated) = stderr.join().map_err(|_| "Failed to capture stderr")?;
+
+        let hash = &command.command.hash;
+        let mut log = RunLog::load(hash);
+        log.record(RunRecord {
+            started_at,
+            duration_ms: start.elapsed().as_millis() as u64,
+            status: status.code(),
+            command: command.command.command.clone(),
+            profile: command.profile.clone(),
+            stdout: String::from_utf8_lossy(&stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&stderr).into_owned(),
+            truncated: stdout_truncated || stderr_truncated,
+        });
+        if let Err(e) = log.save(hash) {
+            eprintln!("Warning: failed to record the run: {}", e);
+        }
+        Ok(status)
+    }
+
+    /// List the captured runs of a command, or show one of them in full.
+    pub fn list_runs(&self, identifier: &str, run: Option<usize>, format: Option<OutputFormat>) -> Result<(), Box<dyn std::error::Error>> {
+        // Removed commands keep their log, so a bare hash is accepted as well. Only a
+        // real hash though, anything else would name a file outside the runs directory
+        let is_hash = identifier.len() == 8 && identifier.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        let hash = match self.find_command_by_identifier(identifier) {
+            Some(command) => command.command.hash,
+            None if is_hash && RunLog::path(identifier).is_some_and(|path| path.exists()) => identifier.to_string(),
+            None => return Err("Command not found".into()),
+        };
+        let log = RunLog::load(&hash);
+        if log.runs.is_empty() {
+            println!("No captured runs. Use 'sebas run --capture {}' to record one.", identifier);
+            return Ok(());
+        }
+
+        let Some(number) = run else {
+            let summaries: Vec<RunSummary> = log
+                .runs
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, record)| RunSummary {
+                    run: i + 1,
+                    started_at: record.started_at.clone(),
+                    duration_ms: record.duration_ms,
+                    status: record.status,
+                    command: record.command.clone(),
+                })
+                .collect();
+            if let Some(format) = format {
+                return render(&summaries, format, RUN_COLUMNS);
+            }
+            for summary in summaries {
+                let status = match summary.status {
+                    Some(0) => "ok".green(),
+                    Some(code) => format!("exit {}", code).bright_red(),
+                    None => "killed".bright_red(),
+                };
+                println!("  {} {} {} {:>8}  {}", summary.run.to_string().yellow(), local_time(&summary.started_at).dimmed(), status, format!("{}ms", summary.duration_ms), summary.command);
+            }
+            return Ok(());
+        };
+
+        let record = number
+            .checked_sub(1)
+            .and_then(|i| log.runs.iter().rev().nth(i))
+            .ok_or_else(|| format!("No run number {}, there are {}.", number, log.runs.len()))?;
+        if let Some(format) = format {
+            return render(std::slice::from_ref(record), format, &["started_at", "duration_ms", "status", "command", "stdout", "stderr"]);
+        }
+        println!("{} {}", "Command:".bold(), record.command);
+        println!("{} {}", "Started:".bold(), local_time(&record.started_at));
+        println!("{} {}ms", "Duration:".bold(), record.duration_ms);
+        println!("{} {}", "Status:".bold(), record.status.map_or("killed by signal".to_string(), |code| code.to_string()));
+        if let Some(ref profile) = record.profile {
+            println!("{} {}", "Profile:".bold(), profile);
+        }
+        if record.truncated {
+            println!("{}", format!("Output was truncated to its last {} KiB.", CAPTURE_LIMIT / 1024).dimmed());
+        }
+        println!("{}\n{}", "Stdout:".bold(), record.stdout.trim_end());
+        println!("{}\n{}", "Stderr:".bold(), record.stderr.trim_end());
+        Ok(())
+    }
+}
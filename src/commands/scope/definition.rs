@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+/// Which `.sebas` folder of the chain a command writes to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scope {
+    /// The closest folder, the default
+    Nearest,
+    /// The outermost project folder
+    Root,
+    /// The personal store in the home directory
+    Global,
+    /// Ask interactively
+    Pick,
+    Path(PathBuf),
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct ScopeArgs {
+    /// Target folder: nearest, root, global, pick or a path
+    #[arg(long, value_parser = Scope::parse)]
+    pub(crate) scope: Option<Scope>,
+    /// Target folder by position in the chain, 0 being the nearest
+    #[arg(long, conflicts_with = "scope")]
+    pub(crate) level: Option<usize>,
+}
@@ -0,0 +1,96 @@
+use std::{io::{self, Write}, path::PathBuf};
+
+use crate::{commands::scope::definition::{Scope, ScopeArgs}, utils::dir::get_all_sebas_dirs, SebasApp};
+
+impl Scope {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        Ok(match value {
+            "nearest" => Self::Nearest,
+            "root" => Self::Root,
+            "global" => Self::Global,
+            "pick" => Self::Pick,
+            path => Self::Path(PathBuf::from(path)),
+        })
+    }
+}
+
+impl ScopeArgs {
+    pub fn is_set(&self) -> bool {
+        self.scope.is_some() || self.level.is_some()
+    }
+}
+
+impl SebasApp {
+    fn global_sebas_dir() -> Option<PathBuf> {
+        home::home_dir().map(|home| home.join(".sebas"))
+    }
+
+    /// Let the user choose among the folders of the chain, `None` when cancelled.
+    pub fn pick_sebas_dir(&self) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+        let dirs = get_all_sebas_dirs();
+        if dirs.len() <= 1 {
+            return Ok(Some(self.sebas_dir.clone()));
+        }
+
+        println!("Save to:");
+        for (i, dir) in dirs.iter().enumerate() {
+            let marker = if *dir == self.sebas_dir { " (default)" } else { "" };
+            println!("  {}) {}{}", i + 1, dir.display(), marker);
+        }
+        let default = dirs.iter().position(|dir| *dir == self.sebas_dir).unwrap_or(0) + 1;
+        print!("Folder [{}]: ", default);
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        let choice = input.trim();
+        let index = if choice.is_empty() {
+            default
+        } else {
+            choice.parse::<usize>().map_err(|_| format!("'{}' is not a folder number.", choice))?
+        };
+        let dir = dirs.get(index.wrapping_sub(1)).ok_or_else(|| format!("No folder number {}.", index))?;
+        Ok(Some(dir.clone()))
+    }
+
+    /// The folder selected by `--scope` or `--level`, the nearest one otherwise.
+    pub fn scoped_dir(&self, args: &ScopeArgs) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let dirs = get_all_sebas_dirs();
+        if let Some(level) = args.level {
+            return dirs
+                .get(level)
+                .cloned()
+                .ok_or_else(|| format!("Level {} is out of range, there are {} .sebas folders above here.", level, dirs.len()).into());
+        }
+
+        let global = Self::global_sebas_dir();
+        let dir = match args.scope.clone().unwrap_or(Scope::Nearest) {
+            Scope::Nearest => self.sebas_dir.clone(),
+            // The outermost folder that is not the personal store
+            Scope::Root => dirs
+                .iter()
+                .rev()
+                .find(|dir| Some(*dir) != global.as_ref())
+                .cloned()
+                .unwrap_or_else(|| self.sebas_dir.clone()),
+            Scope::Global => global
+                .filter(|dir| dir.is_dir())
+                .ok_or("No global store. Run 'sebas init ~' to create one.")?,
+            Scope::Pick => self.pick_sebas_dir()?.ok_or("No folder chosen.")?,
+            Scope::Path(path) => {
+                let dir = Self::resolve_sebas_path(Some(path), &self.sebas_dir);
+                dir.canonicalize().map_err(|_| format!("No .sebas folder at {}", dir.display()))?
+            }
+        };
+        if !dir.is_dir() {
+            return Err(format!("No .sebas folder at {}", dir.display()).into());
+        }
+        Ok(dir)
+    }
+
+    pub fn scoped(&self, args: &ScopeArgs) -> Result<SebasApp, Box<dyn std::error::Error>> {
+        Ok(SebasApp { sebas_dir: self.scoped_dir(args)? })
+    }
+}
@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+/// Directories `sebas run` fans out to instead of running once.
+#[derive(Args, Debug, Clone, Default)]
+pub struct TargetArgs {
+    /// Run in every directory matching this glob, `**` matches any depth
+    #[arg(long = "in", value_name = "GLOB")]
+    pub(crate) pattern: Option<String>,
+    /// Run in every directory listed in this file, one path or glob per line relative to the file
+    #[arg(long, value_name = "FILE")]
+    pub(crate) targets: Option<PathBuf>,
+    /// Targets run at the same time (defaults to the number of CPUs)
+    #[arg(short, long)]
+    pub(crate) jobs: Option<usize>,
+}
+
+/// How one target of a fanned out run ended.
+#[derive(Debug, Clone)]
+pub struct TargetResult {
+    pub(crate) target: String,
+    /// `None` when killed by a signal or not started
+    pub(crate) status: Option<i32>,
+    pub(crate) duration_ms: u64,
+    /// Why the command could not be started
+    pub(crate) error: Option<String>,
+}
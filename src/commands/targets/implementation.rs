@@ -0,0 +1,139 @@
+use std::{fs, io::{BufRead, BufReader, Read, Write}, path::{Path, PathBuf}, process::Stdio, sync::{atomic::{AtomicUsize, Ordering}, Mutex}, thread, time::Instant};
+
+use colored::{Color, Colorize};
+
+use crate::{commands::{core::definition::ResolvedCommand, targets::definition::{TargetArgs, TargetResult}}, utils::glob::matching_dirs, SebasApp};
+
+// Prefix colours, cycled through the targets
+const PALETTE: &[Color] = &[Color::Cyan, Color::Magenta, Color::Yellow, Color::Blue, Color::Green, Color::BrightCyan, Color::BrightMagenta, Color::BrightBlue];
+
+impl TargetArgs {
+    pub fn is_set(&self) -> bool {
+        self.pattern.is_some() || self.targets.is_some()
+    }
+
+    fn expand(base: &Path, pattern: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let dirs = matching_dirs(base, pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+        if dirs.is_empty() {
+            return Err(format!("No directories match '{}'.", pattern).into());
+        }
+        Ok(dirs)
+    }
+
+    /// Every directory selected by `--in` and `--targets`, each once.
+    pub fn resolve(&self) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut dirs = Vec::new();
+        if let Some(ref pattern) = self.pattern {
+            dirs.extend(Self::expand(Path::new("."), pattern)?);
+        }
+        if let Some(ref file) = self.targets {
+            let content = fs::read_to_string(file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            // Lines are relative to the file, so it works from any directory
+            let base = file.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+            for line in content.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+                dirs.extend(Self::expand(base, line)?);
+            }
+        }
+        let mut seen = Vec::new();
+        dirs.retain(|dir| {
+            let fresh = !seen.contains(dir);
+            seen.push(dir.clone());
+            fresh
+        });
+        Ok(dirs)
+    }
+}
+
+// Print every line of `source` behind the target's prefix, whole lines at a time
+fn relay(source: impl Read, prefix: &str, to_stderr: bool) {
+    for line in BufReader::new(source).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if to_stderr {
+            let _ = writeln!(std::io::stderr().lock(), "{} {}", prefix, line);
+        } else {
+            let _ = writeln!(std::io::stdout().lock(), "{} {}", prefix, line);
+        }
+    }
+}
+
+fn run_target(command: &ResolvedCommand, dir: &Path, prefix: &str) -> TargetResult {
+    let start = Instant::now();
+    let target = dir.display().to_string();
+    let mut process = command.process();
+    process.current_dir(dir).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => return TargetResult { target, status: None, duration_ms: 0, error: Some(e.to_string()) },
+    };
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    thread::scope(|scope| {
+        if let Some(stdout) = stdout {
+            scope.spawn(|| relay(stdout, prefix, false));
+        }
+        if let Some(stderr) = stderr {
+            scope.spawn(|| relay(stderr, prefix, true));
+        }
+    });
+
+    let (status, error) = match child.wait() {
+        Ok(status) => (status.code(), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    TargetResult { target, status, duration_ms: start.elapsed().as_millis() as u64, error }
+}
+
+impl SebasApp {
+    /// Run `command` in each of `dirs`, at most `jobs` at a time, then summarise
+    /// the exit codes. Fails if any target failed.
+    pub fn run_in_targets(command: &ResolvedCommand, dirs: &[PathBuf], jobs: Option<usize>) -> Result<(), Box<dyn std::error::Error>> {
+        let jobs = jobs
+            .unwrap_or_else(|| thread::available_parallelism().map_or(4, usize::from))
+            .clamp(1, dirs.len().max(1));
+        let width = dirs.iter().map(|dir| dir.display().to_string().chars().count()).max().unwrap_or(0);
+        let prefixes: Vec<String> = dirs
+            .iter()
+            .enumerate()
+            .map(|(i, dir)| format!("[{:width$}]", dir.display(), width = width).color(PALETTE[i % PALETTE.len()]).to_string())
+            .collect();
+
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<TargetResult>>> = Mutex::new(vec![None; dirs.len()]);
+        thread::scope(|scope| {
+            for _ in 0..jobs {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(dir) = dirs.get(i) else {
+                        break;
+                    };
+                    let result = run_target(command, dir, &prefixes[i]);
+                    if let Ok(mut results) = results.lock() {
+                        results[i] = Some(result);
+                    }
+                });
+            }
+        });
+        let results: Vec<TargetResult> = results.into_inner().map_err(|_| "A worker panicked")?.into_iter().flatten().collect();
+
+        println!();
+        println!("{}", "Summary:".bold());
+        for result in &results {
+            let status = match (result.status, &result.error) {
+                (_, Some(error)) => format!("error: {}", error).bright_red(),
+                (Some(0), None) => "ok".green(),
+                (Some(code), None) => format!("exit {}", code).bright_red(),
+                (None, None) => "killed".bright_red(),
+            };
+            println!("  {:width$}  {:>8}  {}", result.target, format!("{}ms", result.duration_ms), status, width = width);
+        }
+        let failed = results.iter().filter(|result| result.status != Some(0)).count();
+        if failed > 0 {
+            return Err(format!("{} of {} targets failed.", failed, results.len()).into());
+        }
+        println!("All {} targets succeeded.", results.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+
+use crate::commands::core::definition::SavedCommand;
+
+#[derive(Subcommand)]
+pub enum TrashAction {
+    /// List removed commands and groups
+    #[command(alias = "ls")]
+    List,
+    /// Put a removed command or group back
+    Restore {
+        /// Trash entry id from 'sebas trash list'
+        id: String,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Permanently delete everything in the trash
+    Empty {
+        /// Only empty the trash of this .sebas folder
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+        /// Skip confirmation prompts
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+/// A removed command or group, stored as `.sebas/.trash/<id>.yaml`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrashEntry {
+    pub(crate) id: String,
+    pub(crate) deleted_at: String,
+    /// Group the command was removed from, or the removed group
+    pub(crate) group: String,
+    #[serde(flatten)]
+    pub(crate) item: TrashItem,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TrashItem {
+    Command {
+        command: Box<SavedCommand>,
+    },
+    /// The group file as it was on disk, encrypted groups stay encrypted
+    Group {
+        file_name: String,
+        content: String,
+    },
+}
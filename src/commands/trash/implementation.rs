@@ -0,0 +1,176 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use chrono::{DateTime, Duration, Local, Utc};
+use colored::Colorize;
+
+use crate::{commands::{core::definition::FolderConfig, trash::definition::{TrashEntry, TrashItem}}, utils::dir::{get_all_sebas_dirs, is_valid_group_name, write_private}, SebasApp};
+
+impl TrashEntry {
+    pub fn new(group: &str, item: TrashItem) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            deleted_at: Utc::now().to_rfc3339(),
+            group: group.to_string(),
+            item,
+        }
+    }
+
+    pub fn dir(sebas_dir: &Path) -> PathBuf {
+        sebas_dir.join(".trash")
+    }
+
+    pub fn path(&self, sebas_dir: &Path) -> PathBuf {
+        Self::dir(sebas_dir).join(format!("{}.yaml", self.id))
+    }
+
+    pub fn write(&self, sebas_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        write_private(&self.path(sebas_dir), &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Entries of one folder, oldest first.
+    pub fn load_all(sebas_dir: &Path) -> Vec<TrashEntry> {
+        let mut entries: Vec<TrashEntry> = fs::read_dir(Self::dir(sebas_dir))
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+                    .filter_map(|content| serde_yaml::from_str(&content).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort_by(|a, b| a.deleted_at.cmp(&b.deleted_at));
+        entries
+    }
+
+    fn describe(&self) -> String {
+        match &self.item {
+            TrashItem::Command { command } => format!("{}: {}", self.group, command.command),
+            TrashItem::Group { .. } => format!("group {}", self.group),
+        }
+    }
+}
+
+impl SebasApp {
+    /// Drop entries older than the folder's `trash.expire_days`.
+    pub fn expire_trash(sebas_dir: &Path) {
+        let days = FolderConfig::load(sebas_dir).trash.expire_days;
+        if days <= 0 {
+            return;
+        }
+        let cutoff = Utc::now() - Duration::days(days);
+        for entry in TrashEntry::load_all(sebas_dir) {
+            let expired = DateTime::parse_from_rfc3339(&entry.deleted_at).is_ok_and(|at| at < cutoff);
+            if expired {
+                let _ = fs::remove_file(entry.path(sebas_dir));
+            }
+        }
+    }
+
+    pub fn trash_list(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut empty = true;
+        for dir in get_all_sebas_dirs() {
+            Self::expire_trash(&dir);
+            let entries = TrashEntry::load_all(&dir);
+            if entries.is_empty() {
+                continue;
+            }
+            empty = false;
+            println!("{}", dir.display().to_string().bold());
+            for entry in entries {
+                let deleted_at = DateTime::parse_from_rfc3339(&entry.deleted_at)
+                    .map(|at| at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or(entry.deleted_at.clone());
+                println!("  {} {} {}", entry.id.yellow(), deleted_at.dimmed(), entry.describe());
+            }
+        }
+        if empty {
+            println!("Trash is empty.");
+        }
+        Ok(())
+    }
+
+    pub fn trash_restore(&self, id: &str, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut matches: Vec<(PathBuf, TrashEntry)> = get_all_sebas_dirs()
+            .into_iter()
+            .flat_map(|dir| {
+                TrashEntry::load_all(&dir)
+                    .into_iter()
+                    .filter(|entry| entry.id.starts_with(id))
+                    .map(move |entry| (dir.clone(), entry))
+            })
+            .collect();
+        if matches.len() > 1 {
+            let ids: Vec<&str> = matches.iter().map(|(_, entry)| entry.id.as_str()).collect();
+            return Err(format!("Trash id '{}' is ambiguous, it matches {}.", id, ids.join(", ")).into());
+        }
+        let (sebas_dir, entry) = matches.pop().ok_or_else(|| format!("No trash entry '{}'.", id))?;
+
+        // The trash is not covered by the trust hash, so a cloned entry must not name files outside the folder
+        if !is_valid_group_name(&entry.group) {
+            return Err(format!("Trash entry '{}' has an invalid group name '{}'.", entry.id, entry.group).into());
+        }
+        if let TrashItem::Group { file_name, .. } = &entry.item {
+            if *file_name != format!("{}.yaml", entry.group) && *file_name != format!("{}.enc", entry.group) {
+                return Err(format!("Trash entry '{}' has an invalid file name '{}'.", entry.id, file_name).into());
+            }
+        }
+
+        if !yes && !Self::confirm(&format!("Restore {}?", entry.describe())) {
+            println!("Restore cancelled.");
+            return Ok(());
+        }
+
+        let app = SebasApp { sebas_dir: sebas_dir.clone() };
+        let trash_file = entry.path(&sebas_dir);
+        let mut files = app.group_files(&entry.group);
+        files.push(trash_file.clone());
+        Self::journaled(format!("restore {}", entry.describe()), files, || {
+            match &entry.item {
+                TrashItem::Command { command } => {
+                    let mut group = app.load_group(&entry.group)?;
+                    if !group.commands.iter().any(|cmd| cmd.hash == command.hash) {
+                        group.commands.push(command.as_ref().clone());
+                        app.save_group(&entry.group, sebas_dir.join(format!("{}.yaml", entry.group)), &group)?;
+                    }
+                }
+                TrashItem::Group { file_name, content } => {
+                    if app.group_files(&entry.group).iter().any(|path| path.exists()) {
+                        return Err(format!("Group '{}' exists again, rename it first.", entry.group).into());
+                    }
+                    Self::write_sebas_dir(&sebas_dir, || Ok(fs::write(sebas_dir.join(file_name), content)?))?;
+                }
+            }
+            Ok(fs::remove_file(&trash_file)?)
+        })?;
+
+        println!("Restored {}.", entry.describe());
+        Ok(())
+    }
+
+    pub fn trash_empty(&self, path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let dirs = match path {
+            Some(path) => vec![Self::resolve_sebas_path(Some(path), &self.sebas_dir)],
+            None => get_all_sebas_dirs(),
+        };
+        let count: usize = dirs.iter().map(|dir| TrashEntry::load_all(dir).len()).sum();
+        if count == 0 {
+            println!("Trash is empty.");
+            return Ok(());
+        }
+
+        if !yes && !Self::confirm(&format!("Permanently delete {} trash entries?", count)) {
+            println!("Trash not emptied.");
+            return Ok(());
+        }
+
+        for dir in dirs {
+            let trash_dir = TrashEntry::dir(&dir);
+            if trash_dir.exists() {
+                fs::remove_dir_all(trash_dir)?;
+            }
+        }
+        println!("Deleted {} trash entries.", count);
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Per-user allowlist of .sebas folders, keyed by canonical path.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TrustStore {
+    /// Content hash of each folder at the time it was trusted
+    pub(crate) folders: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrustState {
+    Trusted,
+    /// Never trusted
+    Unknown,
+    /// Trusted once, but the content changed since
+    Changed,
+}
@@ -0,0 +1,190 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use colored::Colorize;
+use sha2::{Digest, Sha256};
+
+use crate::{commands::{core::definition::ResolvedCommand, trust::definition::{TrustState, TrustStore}}, utils::dir::{find_sebas_dir, get_state_dir, write_private}, SebasApp};
+
+impl TrustStore {
+    pub fn path() -> Option<PathBuf> {
+        get_state_dir().map(|dir| dir.join("trust.yaml"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not determine the sebas state directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn key(sebas_dir: &Path) -> String {
+        sebas_dir
+            .canonicalize()
+            .unwrap_or_else(|_| sebas_dir.to_path_buf())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    // Hash every file of the folder, hidden directories like .git excluded
+    pub fn folder_hash(sebas_dir: &Path) -> String {
+        fn collect(dir: &Path, files: &mut Vec<PathBuf>) {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            for path in entries.flatten().map(|entry| entry.path()) {
+                let hidden = path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+                if path.is_dir() {
+                    if !hidden {
+                        collect(&path, files);
+                    }
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+
+        let mut files = Vec::new();
+        collect(sebas_dir, &mut files);
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for file in files {
+            let relative = file.strip_prefix(sebas_dir).unwrap_or(&file);
+            hasher.update(relative.to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(fs::read(&file).unwrap_or_default());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn state(&self, sebas_dir: &Path) -> TrustState {
+        // The personal store in the home directory is always trusted
+        let home_store = home::home_dir().map(|home| Self::key(&home.join(".sebas")));
+        let key = Self::key(sebas_dir);
+        if home_store.as_deref() == Some(key.as_str()) {
+            return TrustState::Trusted;
+        }
+
+        match self.folders.get(&key) {
+            Some(hash) if *hash == Self::folder_hash(sebas_dir) => TrustState::Trusted,
+            Some(_) => TrustState::Changed,
+            None => TrustState::Unknown,
+        }
+    }
+
+    pub fn is_trusted(&self, sebas_dir: &Path) -> bool {
+        self.state(sebas_dir) == TrustState::Trusted
+    }
+
+    pub fn trust(&mut self, sebas_dir: &Path) {
+        self.folders.insert(Self::key(sebas_dir), Self::folder_hash(sebas_dir));
+    }
+
+    pub fn revoke(&mut self, sebas_dir: &Path) -> bool {
+        self.folders.remove(&Self::key(sebas_dir)).is_some()
+    }
+}
+
+impl SebasApp {
+    /// Refuse commands from folders that are not (or no longer) trusted.
+    pub fn ensure_trusted(command: &ResolvedCommand) -> Result<(), Box<dyn std::error::Error>> {
+        if command.trusted {
+            return Ok(());
+        }
+        let folder = command.folder_path.parent().unwrap_or(&command.folder_path);
+        let reason = match TrustStore::load().state(&command.folder_path) {
+            TrustState::Changed => "changed since it was trusted",
+            _ => "is not trusted",
+        };
+        Err(format!(
+            "{} {}. Review it with 'sebas trust {}' first.",
+            command.folder_path.display(),
+            reason,
+            folder.display()
+        )
+        .into())
+    }
+
+    pub fn untrusted_marker(command: &ResolvedCommand) -> String {
+        if command.trusted { String::new() } else { format!(" {}", "[untrusted]".yellow()) }
+    }
+
+    pub fn resolve_sebas_path(path: Option<PathBuf>, default: &Path) -> PathBuf {
+        match path {
+            Some(p) if p.file_name().and_then(|n| n.to_str()) == Some(".sebas") => p,
+            Some(p) => p.join(".sebas"),
+            None => default.to_path_buf(),
+        }
+    }
+
+    // A folder given on the command line does not need a .sebas around the current directory
+    fn trust_target(path: Option<PathBuf>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        match path {
+            Some(path) => Ok(Self::resolve_sebas_path(Some(path), Path::new(".sebas"))),
+            None => find_sebas_dir().ok_or_else(|| "No .sebas folder found. Pass the folder to use.".into()),
+        }
+    }
+
+    pub fn trust_folder(path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let sebas_dir = Self::trust_target(path)?;
+        if !sebas_dir.is_dir() {
+            return Err(format!("No .sebas folder at {}", sebas_dir.display()).into());
+        }
+
+        let mut store = TrustStore::load();
+        match store.state(&sebas_dir) {
+            TrustState::Trusted => {
+                println!("{} is already trusted.", sebas_dir.display());
+                return Ok(());
+            }
+            TrustState::Changed => println!("{} changed since it was trusted.", sebas_dir.display()),
+            TrustState::Unknown => println!("{} is not trusted yet.", sebas_dir.display()),
+        }
+
+        // Show what is about to be trusted
+        let app = SebasApp { sebas_dir: sebas_dir.clone() };
+        for group in app.get_all_groups()? {
+            match app.load_group(&group) {
+                Ok(data) => {
+                    println!("{} ({} commands)", group.bold(), data.commands.len());
+                    for cmd in data.commands {
+                        println!("  {}", cmd.command);
+                    }
+                }
+                Err(_) => println!("{} (encrypted, locked)", group.bold()),
+            }
+        }
+        if sebas_dir.join(".config.yaml").exists() {
+            println!("{} {}", "Settings:".bold(), sebas_dir.join(".config.yaml").display());
+        }
+
+        if !yes && !Self::confirm(&format!("Trust {}?", sebas_dir.display())) {
+            println!("Folder not trusted.");
+            return Ok(());
+        }
+
+        store.trust(&sebas_dir);
+        store.save()?;
+        println!("Trusted {}.", sebas_dir.display());
+        Ok(())
+    }
+
+    pub fn untrust_folder(path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let sebas_dir = Self::trust_target(path)?;
+        let mut store = TrustStore::load();
+        if store.revoke(&sebas_dir) {
+            store.save()?;
+            println!("No longer trusting {}.", sebas_dir.display());
+        } else {
+            println!("{} was not trusted.", sebas_dir.display());
+        }
+        Ok(())
+    }
+}
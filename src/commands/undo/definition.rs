@@ -0,0 +1,25 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Recent mutations, kept in the state dir so `sebas undo` can reverse them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UndoJournal {
+    pub(crate) entries: Vec<UndoEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UndoEntry {
+    pub(crate) label: String,
+    pub(crate) at: String,
+    pub(crate) files: Vec<FileSnapshot>,
+}
+
+/// A file touched by a mutation, `None` meaning it did not exist.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileSnapshot {
+    pub(crate) path: PathBuf,
+    pub(crate) before: Option<String>,
+    /// SHA-256 of the content the mutation left behind
+    pub(crate) after_hash: Option<String>,
+}
@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use sha2::{Digest, Sha256};
+
+use crate::{commands::undo::definition::{FileSnapshot, UndoEntry, UndoJournal}, utils::dir::{get_state_dir, write_private}, SebasApp};
+
+const JOURNAL_LIMIT: usize = 20;
+
+impl UndoJournal {
+    pub fn path() -> Option<PathBuf> {
+        get_state_dir().map(|dir| dir.join("undo.yaml"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not determine the sebas state directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn content_hash(path: &Path) -> Option<String> {
+        fs::read(path).ok().map(|content| format!("{:x}", Sha256::digest(content)))
+    }
+}
+
+impl SebasApp {
+    /// Run a mutation touching `files`, remembering their previous content for `sebas undo`.
+    pub fn journaled<T>(label: String, files: Vec<PathBuf>, write: impl FnOnce() -> Result<T, Box<dyn std::error::Error>>) -> Result<T, Box<dyn std::error::Error>> {
+        let before: Vec<(PathBuf, Option<String>)> = files
+            .into_iter()
+            .map(|path| {
+                let content = fs::read_to_string(&path).ok();
+                (path, content)
+            })
+            .collect();
+
+        let result = write()?;
+
+        let files = before
+            .into_iter()
+            .map(|(path, before)| FileSnapshot {
+                after_hash: UndoJournal::content_hash(&path),
+                path,
+                before,
+            })
+            .filter(|snapshot| snapshot.after_hash != snapshot.before.as_ref().map(|c| format!("{:x}", Sha256::digest(c))))
+            .collect::<Vec<_>>();
+        if files.is_empty() {
+            return Ok(result);
+        }
+
+        let mut journal = UndoJournal::load();
+        journal.entries.push(UndoEntry {
+            label,
+            at: chrono::Utc::now().to_rfc3339(),
+            files,
+        });
+        let excess = journal.entries.len().saturating_sub(JOURNAL_LIMIT);
+        journal.entries.drain(..excess);
+        if let Err(e) = journal.save() {
+            eprintln!("Warning: failed to record undo information: {}", e);
+        }
+        Ok(result)
+    }
+
+    // Trash entries live one level below the .sebas folder they belong to
+    fn owning_sebas_dir(path: &Path) -> PathBuf {
+        let parent = path.parent().unwrap_or(path);
+        match parent.file_name().and_then(|n| n.to_str()) {
+            Some(".trash") => parent.parent().unwrap_or(parent).to_path_buf(),
+            _ => parent.to_path_buf(),
+        }
+    }
+
+    pub fn undo(&self, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut journal = UndoJournal::load();
+        let Some(entry) = journal.entries.last() else {
+            println!("Nothing to undo.");
+            return Ok(());
+        };
+
+        // Refuse when something else touched the files since. A created file
+        // that is already gone, like an expired trash entry, is fine.
+        for snapshot in &entry.files {
+            let current = UndoJournal::content_hash(&snapshot.path);
+            if current != snapshot.after_hash && !(current.is_none() && snapshot.before.is_none()) {
+                return Err(format!(
+                    "Cannot undo '{}': {} changed since.",
+                    entry.label,
+                    snapshot.path.display()
+                )
+                .into());
+            }
+        }
+
+        if !yes && !Self::confirm(&format!("Undo '{}'?", entry.label)) {
+            println!("Undo cancelled.");
+            return Ok(());
+        }
+
+        let mut by_dir: BTreeMap<PathBuf, Vec<&FileSnapshot>> = BTreeMap::new();
+        for snapshot in &entry.files {
+            by_dir.entry(Self::owning_sebas_dir(&snapshot.path)).or_default().push(snapshot);
+        }
+        for (dir, snapshots) in by_dir {
+            Self::write_sebas_dir(&dir, || {
+                for snapshot in snapshots {
+                    match &snapshot.before {
+                        Some(content) => fs::write(&snapshot.path, content)?,
+                        None if snapshot.path.exists() => fs::remove_file(&snapshot.path)?,
+                        None => {}
+                    }
+                }
+                Ok(())
+            })?;
+        }
+
+        let label = entry.label.clone();
+        journal.entries.pop();
+        journal.save()?;
+        println!("Undid '{}'.", label);
+        Ok(())
+    }
+}
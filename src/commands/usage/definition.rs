@@ -0,0 +1,25 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Per-user usage data, keyed by command hash. Lives in the state dir,
+/// never in the shared group files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UsageStore {
+    pub(crate) commands: BTreeMap<String, UsageEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UsageEntry {
+    pub(crate) count: u32,
+    pub(crate) last_used: String,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Most frequently and recently used first
+    Frecency,
+    /// Order in which commands are stored
+    Index,
+}
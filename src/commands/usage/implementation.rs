@@ -0,0 +1,117 @@
+use std::{fs, path::PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::{commands::{core::definition::ResolvedCommand, usage::definition::{UsageEntry, UsageStore}}, utils::dir::{get_state_dir, write_private}, SebasApp};
+
+impl UsageStore {
+    pub fn path() -> Option<PathBuf> {
+        get_state_dir().map(|dir| dir.join("usage.yaml"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_yaml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = Self::path().ok_or("Could not determine the sebas state directory")?;
+        write_private(&path, &serde_yaml::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, hash: &str) {
+        let now = Utc::now().to_rfc3339();
+        let entry = self.commands.entry(hash.to_string()).or_insert(UsageEntry {
+            count: 0,
+            last_used: now.clone(),
+        });
+        entry.count += 1;
+        entry.last_used = now;
+    }
+
+    pub fn get(&self, hash: &str) -> Option<&UsageEntry> {
+        self.commands.get(hash)
+    }
+
+    // Use count weighted by how recently the command was last used
+    pub fn frecency(&self, hash: &str) -> f64 {
+        let Some(entry) = self.get(hash) else {
+            return 0.0;
+        };
+        let weight = match DateTime::parse_from_rfc3339(&entry.last_used) {
+            Ok(last_used) => match (Utc::now() - last_used.with_timezone(&Utc)).num_days() {
+                0..=3 => 100.0,
+                4..=14 => 70.0,
+                15..=31 => 50.0,
+                32..=90 => 30.0,
+                _ => 10.0,
+            },
+            Err(_) => 10.0,
+        };
+        entry.count as f64 * weight
+    }
+
+    pub fn sort_by_frecency(&self, commands: &mut [ResolvedCommand]) {
+        commands.sort_by(|a, b| {
+            self.frecency(&b.command.hash)
+                .total_cmp(&self.frecency(&a.command.hash))
+        });
+    }
+}
+
+impl SebasApp {
+    pub fn record_usage(&self, command: &ResolvedCommand) -> Result<(), Box<dyn std::error::Error>> {
+        let mut usage = UsageStore::load();
+        usage.record(&command.command.hash);
+        usage.save()
+    }
+
+    pub fn stats_command(&self, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let resolved = self.resolve_all_commands();
+
+        if resolved.is_empty() {
+            println!("No commands saved.");
+            return Ok(());
+        }
+
+        let usage = UsageStore::load();
+        let (mut used, never_used): (Vec<_>, Vec<_>) = resolved
+            .into_iter()
+            .partition(|cmd| usage.get(&cmd.command.hash).is_some());
+        used.sort_by_key(|cmd| std::cmp::Reverse(usage.get(&cmd.command.hash).map_or(0, |e| e.count)));
+
+        let print_used = |cmd: &ResolvedCommand| {
+            if let Some(entry) = usage.get(&cmd.command.hash) {
+                println!("  [{}] {} ({}) - {} uses, last {}",
+                    cmd.index,
+                    cmd.command.command,
+                    cmd.group,
+                    entry.count,
+                    entry.last_used
+                );
+            }
+        };
+
+        println!("Most used:");
+        if used.is_empty() {
+            println!("  No usage recorded yet.");
+        }
+        used.iter().take(limit).for_each(print_used);
+
+        println!("Least used:");
+        if used.is_empty() {
+            println!("  No usage recorded yet.");
+        }
+        used.iter().rev().take(limit).for_each(print_used);
+
+        println!("Never used ({}):", never_used.len());
+        for cmd in &never_used {
+            println!("  [{}] {} ({})", cmd.index, cmd.command.command, cmd.group);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use colored::Colorize;
+use git2::{Commit, Delta, DiffFormat, IndexAddOption, Repository, RevertOptions, Signature, StatusOptions};
+
+use crate::SebasApp;
+
+impl SebasApp {
+    // Versioning is opt-in: only folders with their own repository are tracked
+    fn open_history(sebas_dir: &Path) -> Option<Repository> {
+        sebas_dir
+            .join(".git")
+            .is_dir()
+            .then(|| Repository::open(sebas_dir).ok())
+            .flatten()
+    }
+
+    fn signature(repo: &Repository) -> Result<Signature<'static>, git2::Error> {
+        repo.signature().or_else(|_| Signature::now("sebas", "sebas@localhost"))
+    }
+
+    pub fn enable_history(sebas_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if Self::open_history(sebas_dir).is_some() {
+            println!("History is already enabled for {}.", sebas_dir.display());
+            return Ok(());
+        }
+        Repository::init(sebas_dir)?;
+        Self::commit_sebas_dir(sebas_dir)?;
+        println!("History enabled for {}.", sebas_dir.display());
+        Ok(())
+    }
+
+    /// Commit everything that changed in a versioned folder, a no-op otherwise.
+    pub fn commit_sebas_dir(sebas_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(repo) = Self::open_history(sebas_dir) else {
+            return Ok(());
+        };
+
+        let mut index = repo.index()?;
+        // The trash is local recovery state, not part of the history
+        let mut skip_trash = |path: &Path, _: &[u8]| if path.starts_with(".trash") { 1 } else { 0 };
+        index.add_all(["*"], IndexAddOption::DEFAULT, Some(&mut skip_trash))?;
+        index.update_all(["*"], None)?;
+        index.write()?;
+
+        // Describe the staged changes, e.g. "Update ops.yaml, Remove old.yaml"
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).renames_head_to_index(true);
+        let mut changes = Vec::new();
+        for entry in repo.statuses(Some(&mut options))?.iter() {
+            let Some(delta) = entry.head_to_index() else {
+                continue;
+            };
+            let old = delta.old_file().path().map(|p| p.display().to_string()).unwrap_or_default();
+            let new = delta.new_file().path().map(|p| p.display().to_string()).unwrap_or_default();
+            changes.push(match delta.status() {
+                Delta::Added => format!("Add {}", new),
+                Delta::Deleted => format!("Remove {}", old),
+                Delta::Renamed => format!("Rename {} to {}", old, new),
+                _ => format!("Update {}", new),
+            });
+        }
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let tree = repo.find_tree(index.write_tree()?)?;
+        let signature = Self::signature(&repo)?;
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, &changes.join(", "), &tree, &parents)?;
+        Ok(())
+    }
+
+    fn history_for(&self, path: Option<PathBuf>) -> Result<(PathBuf, Repository), Box<dyn std::error::Error>> {
+        let sebas_dir = Self::resolve_sebas_path(path, &self.sebas_dir);
+        let repo = Self::open_history(&sebas_dir).ok_or_else(|| {
+            format!("History is not enabled for {}. Run 'sebas init --git' to enable it.", sebas_dir.display())
+        })?;
+        Ok((sebas_dir, repo))
+    }
+
+    pub fn log_history(&self, path: Option<PathBuf>, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let (_, repo) = self.history_for(path)?;
+        let mut revwalk = repo.revwalk()?;
+        if revwalk.push_head().is_err() {
+            println!("No changes recorded yet.");
+            return Ok(());
+        }
+
+        for oid in revwalk.take(limit) {
+            let commit = repo.find_commit(oid?)?;
+            let time = DateTime::from_timestamp(commit.time().seconds(), 0)
+                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_default();
+            println!(
+                "{} {} {}",
+                commit.id().to_string()[..8].yellow(),
+                time.dimmed(),
+                commit.summary().unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn diff_history(&self, rev: Option<String>, path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+        let (_, repo) = self.history_for(path)?;
+
+        // A revision is compared with its parent, otherwise uncommitted edits with HEAD
+        let diff = match rev {
+            Some(rev) => {
+                let commit = repo.revparse_single(&rev)?.peel_to_commit()?;
+                let parent = commit.parent(0).ok().map(|p| p.tree()).transpose()?;
+                repo.diff_tree_to_tree(parent.as_ref(), Some(&commit.tree()?), None)?
+            }
+            None => {
+                let head = repo.head().ok().map(|h| h.peel_to_tree()).transpose()?;
+                repo.diff_tree_to_workdir_with_index(head.as_ref(), None)?
+            }
+        };
+
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            let content = String::from_utf8_lossy(line.content());
+            match line.origin() {
+                '+' => print!("{}", format!("+{}", content).green()),
+                '-' => print!("{}", format!("-{}", content).red()),
+                ' ' => print!(" {}", content),
+                _ => print!("{}", content.bold()),
+            }
+            true
+        })?;
+        Ok(())
+    }
+
+    pub fn revert_history(&self, rev: &str, path: Option<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let (sebas_dir, repo) = self.history_for(path)?;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        let summary = commit.summary().unwrap_or_default().to_string();
+
+        if !yes && !Self::confirm(&format!("Revert '{}' ({})?", summary, &commit.id().to_string()[..8])) {
+            println!("Revert cancelled.");
+            return Ok(());
+        }
+
+        Self::write_sebas_dir(&sebas_dir, || {
+            let mut options = RevertOptions::new();
+            if commit.parent_count() > 1 {
+                options.mainline(1);
+            }
+            repo.revert(&commit, Some(&mut options))?;
+
+            let mut index = repo.index()?;
+            if index.has_conflicts() {
+                repo.cleanup_state()?;
+                repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+                return Err(format!("'{}' conflicts with later changes and cannot be reverted.", summary).into());
+            }
+
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let signature = Self::signature(&repo)?;
+            let head = repo.head()?.peel_to_commit()?;
+            repo.commit(Some("HEAD"), &signature, &signature, &format!("Revert \"{}\"", summary), &tree, &[&head])?;
+            repo.cleanup_state()?;
+            Ok(())
+        })?;
+
+        println!("Reverted '{}'.", summary);
+        Ok(())
+    }
+}
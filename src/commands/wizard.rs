@@ -0,0 +1,105 @@
+use rustyline::{completion::{Completer, Pair}, error::ReadlineError, highlight::Highlighter, hint::Hinter, history::DefaultHistory, validate::Validator, Context, Editor, Helper};
+
+use crate::{utils::dir::get_all_sebas_dirs, SebasApp};
+
+/// Completes group names on the group prompt, nothing elsewhere.
+#[derive(Default)]
+struct WizardHelper {
+    groups: Vec<String>,
+}
+
+impl Completer for WizardHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let prefix = &line[..pos];
+        let candidates = self
+            .groups
+            .iter()
+            .filter(|group| group.starts_with(prefix))
+            .map(|group| Pair { display: group.clone(), replacement: group.clone() })
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for WizardHelper {
+    type Hint = String;
+}
+
+impl Highlighter for WizardHelper {}
+
+impl Validator for WizardHelper {}
+
+impl Helper for WizardHelper {}
+
+type WizardEditor = Editor<WizardHelper, DefaultHistory>;
+
+// `None` when the user pressed Ctrl-C or Ctrl-D
+fn prompt(editor: &mut WizardEditor, label: &str, initial: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match editor.readline_with_initial(label, (initial, "")) {
+        Ok(line) => Ok(Some(line.trim().to_string())),
+        Err(ReadlineError::Interrupted | ReadlineError::Eof) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+impl SebasApp {
+    /// Ask for everything `sebas add` needs, starting from the last shell command.
+    /// The folder is only asked for when `pick_folder`, `--scope` already chose one.
+    pub fn add_wizard(&self, group: Option<String>, comment: Option<String>, name: Option<String>, danger: bool, pick_folder: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut editor = WizardEditor::new()?;
+        editor.set_helper(Some(WizardHelper::default()));
+
+        let last = Self::get_last_shell_command().unwrap_or_default();
+        let command = match prompt(&mut editor, "Command: ", &last)? {
+            Some(command) if !command.is_empty() => command,
+            _ => {
+                println!("Command not saved.");
+                return Ok(());
+            }
+        };
+
+        // Any folder of the chain can receive the command, the nearest is the default
+        if !pick_folder || get_all_sebas_dirs().len() <= 1 {
+            println!("Saving to {}", self.sebas_dir.display());
+        }
+        let sebas_dir = if pick_folder { self.pick_sebas_dir()? } else { Some(self.sebas_dir.clone()) };
+        let Some(sebas_dir) = sebas_dir else {
+            println!("Command not saved.");
+            return Ok(());
+        };
+
+        let app = SebasApp { sebas_dir };
+        if let Some(helper) = editor.helper_mut() {
+            helper.groups = app.get_all_groups()?;
+        }
+        let initial_group = group.unwrap_or_else(|| "Miscellaneous".to_string());
+        let Some(group) = prompt(&mut editor, "Group (tab completes): ", &initial_group)? else {
+            println!("Command not saved.");
+            return Ok(());
+        };
+        if let Some(helper) = editor.helper_mut() {
+            helper.groups.clear();
+        }
+
+        let Some(comment) = prompt(&mut editor, "Comment: ", comment.as_deref().unwrap_or_default())? else {
+            println!("Command not saved.");
+            return Ok(());
+        };
+        let Some(name) = prompt(&mut editor, "Name: ", name.as_deref().unwrap_or_default())? else {
+            println!("Command not saved.");
+            return Ok(());
+        };
+
+        let group = if group.is_empty() { initial_group } else { group };
+        app.add_command(
+            Some(command),
+            Some(group),
+            Some(comment).filter(|c| !c.is_empty()),
+            Some(name).filter(|n| !n.is_empty()),
+            danger,
+            false,
+        )
+    }
+}
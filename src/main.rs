@@ -1,17 +1,21 @@
 mod utils;
 mod commands;
-use clap::{Parser};
+use clap::{CommandFactory, Parser};
+use clap_complete::env::CompleteEnv;
 use std::
     path::PathBuf
 ;
 
-use crate::{commands::{commands::definition::Commands, group::definition::GroupAction}};
+use crate::{commands::{commands::definition::{CommandEdit, Commands}, group::definition::GroupAction, import::definition::ImportAction, pack::definition::PackAction, trash::definition::TrashAction}};
 
 #[derive(Parser)]
 #[command(name = "sebas")]
 #[command(about = "Simply Elegant Bookmarked Alternatives for commandS")]
 #[command(version = "0.0.1")]
 struct Cli {
+    /// Variables for `${var}` in commands, from .sebas/profiles/<PROFILE>.yaml
+    #[arg(long, global = true, env = "SEBAS_PROFILE")]
+    profile: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,28 +26,79 @@ struct SebasApp {
 
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Answers the shell when it asks for completions, returns otherwise
+    CompleteEnv::with_factory(Cli::command).complete();
+
     let cli = Cli::parse();
+    let profile = cli.profile.as_deref();
 
     match cli.command {
-        Commands::Init { path } => {
-            SebasApp::init_folder(path)?;
+        Commands::Init { path, git } => {
+            SebasApp::init_folder(path, git)?;
+        }
+        Commands::Lock => {
+            SebasApp::lock()?;
+        }
+        Commands::Sync => {
+            SebasApp::sync_folders()?;
+        }
+        Commands::Trust { path, yes } => {
+            SebasApp::trust_folder(path, yes)?;
+        }
+        Commands::Untrust { path } => {
+            SebasApp::untrust_folder(path)?;
+        }
+        // A key pair has nothing to do with the .sebas folders
+        Commands::Pack { action: PackAction::Keygen { output } } => {
+            SebasApp::pack_keygen(&output)?;
+        }
+        Commands::ShellHook { shell } => {
+            SebasApp::print_shell_hook(shell);
+        }
+        Commands::Capture { status, command } => {
+            SebasApp::capture_command(&command, status)?;
+        }
+        Commands::Completions { shell, static_only } => {
+            SebasApp::print_completions(shell, static_only)?;
         }
-        //Commands::Sync => {
-        //    SebasApp::sync_folders()?;
-        //}
 
         _ => {
             let app = SebasApp::new()?;
             
             match cli.command {
-                Commands::Add { command, group, comment, yes } => {
-                    app.add_command(command, group, comment, yes)?;
+                Commands::Add { command, group, comment, name, danger, scope, yes } => {
+                    let app = app.scoped(&scope)?;
+                    // Nothing given on a terminal: walk the user through it
+                    if command.is_none() && !yes && atty::is(atty::Stream::Stdin) && atty::is(atty::Stream::Stdout) {
+                        app.add_wizard(group, comment, name, danger, !scope.is_set())?;
+                    } else {
+                        app.add_command(command, group, comment, name, danger, yes)?;
+                    }
                 }
-                Commands::List { group, verbose, plain } => {
-                    app.list_commands(group, verbose, plain)?;
+                Commands::List { group, verbose, plain, sort, format } => {
+                    app.list_commands(group, verbose, plain, sort, format)?;
                 }
-                Commands::Edit { identifier, new_command, new_group, new_comment, yes } => {
-                    app.edit_command(&identifier, new_command, new_group, new_comment, yes)?;
+                Commands::Show { identifier, format } => {
+                    app.show_command(&identifier, format)?;
+                }
+                Commands::Edit { identifier, new_command, new_group, new_comment, new_name, danger, interactive, group, scope, yes } => {
+                    if interactive && scope.is_set() {
+                        return Err("--scope and --level move a command, they do not apply to --interactive.".into());
+                    }
+                    if interactive {
+                        app.edit_interactive(identifier, group, yes)?;
+                    } else {
+                        let identifier = identifier.ok_or("Pass a command identifier or use --interactive with --group.")?;
+                        let changes = CommandEdit {
+                            command: new_command,
+                            group: new_group,
+                            comment: new_comment,
+                            name: new_name,
+                            danger,
+                            folder: scope.is_set().then(|| app.scoped_dir(&scope)).transpose()?,
+                        };
+                        app.edit_command(&identifier, changes, yes)?;
+                    }
                 }
                 Commands::Remove { identifier, yes } => {
                     app.remove_command(&identifier, yes)?;
@@ -51,16 +106,69 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 Commands::History { query } => {
                     app.history_commands(query)?;
                 }
-                Commands::Obtain { identifier } => {
-                    app.obtain_command(identifier)?;
+                Commands::Obtain { identifier, relevant, copy } => {
+                    app.obtain_command(identifier, relevant, copy, profile)?;
+                }
+                Commands::Import { action, from, path, group, yes } => {
+                    match (action, from, path) {
+                        (Some(ImportAction::Tasks { path, group, yes }), _, _) => app.import_tasks(path, group, yes)?,
+                        (None, Some(source), Some(path)) => app.import_cheatsheets(source, &path, group, yes)?,
+                        _ => return Err("Specify 'tasks' or --from <SOURCE> <PATH>.".into()),
+                    }
+                }
+                Commands::Pack { action } => {
+                    match action {
+                        PackAction::Build { groups, key, output, name } => app.pack_build(groups, &key, &output, name)?,
+                        PackAction::Verify { file, signer } => app.pack_verify(&file, signer)?,
+                        PackAction::Install { file, signer, path, yes } => app.pack_install(&file, signer, path, yes)?,
+                        PackAction::Keygen { .. } => unreachable!(),
+                    }
+                }
+                Commands::Export { format, group, output } => {
+                    app.export_commands(format, group, output)?;
+                }
+                Commands::Stats { limit } => {
+                    app.stats_command(limit)?;
+                }
+                Commands::Pick { identifier, relevant } => {
+                    app.pick_command(identifier, relevant, profile)?;
+                }
+                Commands::Run { identifier, relevant, capture, targets } => {
+                    app.run_command(identifier, relevant, capture, &targets, profile)?;
+                }
+                Commands::Runs { identifier, run, format } => {
+                    app.list_runs(&identifier, run, format)?;
+                }
+                Commands::Log { path, limit } => {
+                    app.log_history(path, limit)?;
+                }
+                Commands::Diff { rev, path } => {
+                    app.diff_history(rev, path)?;
+                }
+                Commands::Revert { rev, path, yes } => {
+                    app.revert_history(&rev, path, yes)?;
+                }
+                Commands::Undo { yes } => {
+                    app.undo(yes)?;
+                }
+                Commands::Trash { action } => {
+                    match action {
+                        TrashAction::List => app.trash_list()?,
+                        TrashAction::Restore { id, yes } => app.trash_restore(&id, yes)?,
+                        TrashAction::Empty { path, yes } => app.trash_empty(path, yes)?,
+                    }
+                }
+                Commands::Unlock { key_file, timeout } => {
+                    app.unlock(key_file, timeout)?;
                 }
                 Commands::Group { action } => {
                     match action {
-                        GroupAction::List => app.list_groups()?,
-                        GroupAction::Add { name,path, yes } => app.add_group(&name,path, yes)?,
-                        //GroupAction::Move { old_name,old_path, new_name, new_path, yes } => app.rename_group(&old_name, &new_name, yes)?,
-                        //GroupAction::Remove { name, path, yes } => app.remove_group(&name,&path, yes)?,
-                         _ => unreachable!()
+                        GroupAction::List { format } => app.list_groups(format)?,
+                        GroupAction::Add { name, path, scope, yes } => app.scoped(&scope)?.add_group(&name, path, yes)?,
+                        GroupAction::Move { old_name, old_path, new_name, new_path, yes } => app.rename_group(&old_name, old_path, &new_name, new_path, yes)?,
+                        GroupAction::Remove { name, path, yes } => app.remove_group(&name, path, yes)?,
+                        GroupAction::Encrypt { name, path, key_file, keep_history, yes } => app.encrypt_group(&name, path, key_file, keep_history, yes)?,
+                        GroupAction::Decrypt { name, path, key_file, yes } => app.decrypt_group(&name, path, key_file, yes)?,
                     }
                 }
                 _ => unreachable!(),
@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::commands::import::definition::{ImportSource, ImportedCommand};
+
+// Snippets without tags go to `default_group`, or a group named after their file
+pub fn read_cheatsheets(source: ImportSource, path: &Path, default_group: Option<&str>) -> Result<Vec<ImportedCommand>, Box<dyn std::error::Error>> {
+    let extension = match source {
+        ImportSource::Navi => "cheat",
+        ImportSource::Pet => "toml",
+        ImportSource::Tldr => "md",
+    };
+
+    let mut files = Vec::new();
+    collect_files(path, extension, &mut files)?;
+    files.sort();
+
+    let mut commands = Vec::new();
+    for file in files {
+        let content = fs::read_to_string(&file)?;
+        let fallback = default_group
+            .or_else(|| file.file_stem().and_then(|s| s.to_str()))
+            .unwrap_or("Imported");
+        match source {
+            ImportSource::Navi => commands.extend(parse_navi(&content, fallback)),
+            ImportSource::Pet => commands.extend(parse_pet(&content, fallback)?),
+            ImportSource::Tldr => commands.extend(parse_tldr(&content, fallback)),
+        }
+    }
+    Ok(commands)
+}
+
+fn collect_files(path: &Path, extension: &str, files: &mut Vec<std::path::PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, extension, files)?;
+        } else if path.extension().and_then(|s| s.to_str()) == Some(extension) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Group names end up as file names, so keep them to a safe character set
+fn group_from_tag(tag: &str) -> Option<String> {
+    let name: String = tag
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '-' })
+        .collect();
+    let name = name.trim_matches(['-', '.']).to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+pub fn parse_navi(content: &str, fallback_group: &str) -> Vec<ImportedCommand> {
+    let mut commands = Vec::new();
+    let mut section: Vec<ImportedCommand> = Vec::new();
+    let mut section_vars: BTreeMap<String, String> = BTreeMap::new();
+    let mut group = group_from_tag(fallback_group);
+    let mut description: Option<String> = None;
+    let mut current: Option<String> = None;
+
+    // Variables are scoped to the `%` section they appear in
+    let flush_section = |section: &mut Vec<ImportedCommand>, vars: &mut BTreeMap<String, String>, commands: &mut Vec<ImportedCommand>| {
+        for mut cmd in section.drain(..) {
+            cmd.vars = vars
+                .iter()
+                .filter(|(name, _)| cmd.command.contains(&format!("<{}>", name)))
+                .map(|(name, source)| (name.clone(), source.clone()))
+                .collect();
+            commands.push(cmd);
+        }
+        vars.clear();
+    };
+
+    for line in content.lines().map(str::trim_end).chain(std::iter::once("")) {
+        let is_command_line = !line.is_empty() && !line.starts_with(['%', '#', ';', '$', '@']);
+        if !is_command_line {
+            if let Some(command) = current.take() {
+                section.push(ImportedCommand {
+                    command,
+                    comment: description.take(),
+                    group: group.clone(),
+                    vars: BTreeMap::new(),
+                    cwd: None,
+                });
+            }
+        }
+
+        if let Some(tags) = line.strip_prefix('%') {
+            flush_section(&mut section, &mut section_vars, &mut commands);
+            group = tags.split(',').find_map(group_from_tag).or_else(|| group_from_tag(fallback_group));
+        } else if let Some(comment) = line.strip_prefix('#') {
+            description = Some(comment.trim().to_string());
+        } else if let Some(var) = line.strip_prefix('$') {
+            if let Some((name, source)) = var.split_once(':') {
+                let source = source.split(" --- ").next().unwrap_or_default().trim();
+                section_vars.insert(name.trim().to_string(), source.to_string());
+            }
+        } else if is_command_line {
+            current = Some(match current.take() {
+                Some(previous) => format!("{}\n{}", previous, line),
+                None => line.to_string(),
+            });
+        }
+    }
+    flush_section(&mut section, &mut section_vars, &mut commands);
+
+    commands
+}
+
+#[derive(Deserialize)]
+struct PetSnippets {
+    #[serde(default)]
+    snippets: Vec<PetSnippet>,
+}
+
+#[derive(Deserialize)]
+struct PetSnippet {
+    #[serde(default)]
+    description: String,
+    command: String,
+    #[serde(default)]
+    tag: Vec<String>,
+}
+
+pub fn parse_pet(content: &str, fallback_group: &str) -> Result<Vec<ImportedCommand>, Box<dyn std::error::Error>> {
+    let snippets: PetSnippets = toml::from_str(content)?;
+    Ok(snippets
+        .snippets
+        .into_iter()
+        .map(|snippet| ImportedCommand {
+            command: snippet.command,
+            comment: Some(snippet.description).filter(|d| !d.is_empty()),
+            group: snippet.tag.iter().find_map(|t| group_from_tag(t)).or_else(|| group_from_tag(fallback_group)),
+            vars: BTreeMap::new(),
+            cwd: None,
+        })
+        .collect())
+}
+
+pub fn parse_tldr(content: &str, fallback_group: &str) -> Vec<ImportedCommand> {
+    let mut commands = Vec::new();
+    let mut group = group_from_tag(fallback_group);
+    let mut description: Option<String> = None;
+
+    for line in content.lines().map(str::trim) {
+        if let Some(title) = line.strip_prefix("# ") {
+            group = group_from_tag(title).or(group);
+        } else if let Some(example) = line.strip_prefix("- ") {
+            description = Some(example.trim_end_matches(':').to_string());
+        } else if line.len() > 1 && line.starts_with('`') && line.ends_with('`') {
+            // tldr writes placeholders as {{name}}, sebas uses <name>
+            let command = line[1..line.len() - 1].replace("{{", "<").replace("}}", ">");
+            commands.push(ImportedCommand {
+                command,
+                comment: description.take(),
+                group: group.clone(),
+                vars: BTreeMap::new(),
+                cwd: None,
+            });
+        }
+    }
+
+    commands
+}
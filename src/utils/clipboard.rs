@@ -0,0 +1,55 @@
+use std::{env, fs::OpenOptions, io::Write, process::{Command, Stdio}};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+// Tried in order, each only when its session is present
+const LOCAL_TOOLS: &[(&str, &[&str], &str)] = &[
+    ("wl-copy", &[], "WAYLAND_DISPLAY"),
+    ("xclip", &["-selection", "clipboard"], "DISPLAY"),
+    ("xsel", &["--clipboard", "--input"], "DISPLAY"),
+    ("pbcopy", &[], ""),
+    ("clip.exe", &[], "WSL_DISTRO_NAME"),
+];
+
+fn copy_with_tool(program: &str, args: &[&str], text: &str) -> bool {
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let written = child.stdin.take().is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    written && child.wait().is_ok_and(|status| status.success())
+}
+
+// The terminal sets its clipboard from the escape sequence, which also works over SSH.
+// tmux only forwards it when wrapped in a passthrough sequence.
+fn copy_with_osc52(text: &str) -> std::io::Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", STANDARD.encode(text));
+    let sequence = if env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    };
+    let mut tty = OpenOptions::new().write(true).open("/dev/tty")?;
+    tty.write_all(sequence.as_bytes())?;
+    tty.flush()
+}
+
+/// Put `text` on the clipboard and name the way it got there. Over SSH the local
+/// tools would fill the remote clipboard, so OSC 52 is used right away.
+pub fn copy(text: &str) -> Result<&'static str, std::io::Error> {
+    let remote = env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some();
+    if !remote {
+        for (program, args, session) in LOCAL_TOOLS {
+            if (session.is_empty() || env::var_os(session).is_some()) && copy_with_tool(program, args, text) {
+                return Ok(program);
+            }
+        }
+    }
+    copy_with_osc52(text)?;
+    Ok("OSC 52")
+}
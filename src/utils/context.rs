@@ -0,0 +1,124 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+use crate::commands::core::definition::{ResolvedCommand, SavedCommand};
+
+// Marker file, context name and the tools that belong to that context
+const DETECTORS: &[(&str, &str, &[&str])] = &[
+    ("Cargo.toml", "rust", &["cargo", "rustc", "rustup"]),
+    ("package.json", "node", &["npm", "npx", "yarn", "pnpm", "node", "bun"]),
+    ("Dockerfile", "docker", &["docker"]),
+    ("docker-compose.yml", "docker", &["docker", "docker-compose"]),
+    ("compose.yaml", "docker", &["docker", "docker-compose"]),
+    (".git", "git", &["git", "gh"]),
+    ("kustomization.yaml", "kubernetes", &["kubectl", "kustomize", "helm", "k9s"]),
+    ("Chart.yaml", "kubernetes", &["kubectl", "helm"]),
+    ("go.mod", "go", &["go"]),
+    ("pyproject.toml", "python", &["python", "python3", "pip", "poetry", "uv", "pytest"]),
+    ("requirements.txt", "python", &["python", "python3", "pip", "pytest"]),
+    ("Makefile", "make", &["make"]),
+    ("justfile", "just", &["just"]),
+    ("main.tf", "terraform", &["terraform", "tofu"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relevance {
+    Irrelevant,
+    Neutral,
+    Relevant,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DirContext {
+    pub contexts: Vec<String>,
+    pub branch: Option<String>,
+    ancestors: Vec<PathBuf>,
+}
+
+impl DirContext {
+    pub fn detect() -> Self {
+        let ancestors: Vec<PathBuf> = env::current_dir()
+            .map(|dir| dir.ancestors().map(Path::to_path_buf).collect())
+            .unwrap_or_default();
+
+        let mut contexts = Vec::new();
+        for (marker, name, _) in DETECTORS {
+            if ancestors.iter().any(|dir| dir.join(marker).exists()) && !contexts.iter().any(|c| c == name) {
+                contexts.push(name.to_string());
+            }
+        }
+
+        let branch = ancestors
+            .iter()
+            .find(|dir| dir.join(".git").exists())
+            .and_then(|dir| read_git_branch(&dir.join(".git")));
+
+        Self { contexts, branch, ancestors }
+    }
+
+    fn file_exists(&self, file: &str) -> bool {
+        let path = Path::new(file);
+        if path.is_absolute() {
+            return path.exists();
+        }
+        self.ancestors.iter().any(|dir| dir.join(path).exists())
+    }
+
+    pub fn relevance(&self, command: &SavedCommand) -> Relevance {
+        if let Some(when) = &command.when {
+            let holds = when.files.iter().all(|f| self.file_exists(f))
+                && when.env.iter().all(|v| env::var_os(v).is_some())
+                && when.contexts.iter().all(|c| self.contexts.contains(c))
+                && when.branch.as_ref().is_none_or(|pattern| {
+                    self.branch.as_ref().is_some_and(|b| glob_match(pattern, b))
+                });
+            return if holds { Relevance::Relevant } else { Relevance::Irrelevant };
+        }
+
+        // Without explicit conditions, judge the command by the tool it invokes
+        let Some(tool) = command.command.split_whitespace().find(|word| !word.contains('=') && *word != "sudo") else {
+            return Relevance::Neutral;
+        };
+        let mut known_tool = false;
+        for (_, name, tools) in DETECTORS {
+            if tools.contains(&tool) {
+                if self.contexts.iter().any(|c| c == name) {
+                    return Relevance::Relevant;
+                }
+                known_tool = true;
+            }
+        }
+        if known_tool { Relevance::Irrelevant } else { Relevance::Neutral }
+    }
+
+    /// Move relevant commands to the top, keeping the existing order otherwise.
+    pub fn sort_by_relevance(&self, commands: &mut [ResolvedCommand]) {
+        commands.sort_by_key(|cmd| std::cmp::Reverse(self.relevance(&cmd.command)));
+    }
+}
+
+fn read_git_branch(git_path: &Path) -> Option<String> {
+    // Worktrees and submodules use a `.git` file pointing at the real git dir
+    let git_dir = if git_path.is_file() {
+        let content = fs::read_to_string(git_path).ok()?;
+        let target = PathBuf::from(content.strip_prefix("gitdir:")?.trim());
+        if target.is_absolute() { target } else { git_path.parent()?.join(target) }
+    } else {
+        git_path.to_path_buf()
+    };
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    head.trim().strip_prefix("ref: refs/heads/").map(str::to_string)
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == text,
+        Some((prefix, rest)) => {
+            let Some(remaining) = text.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=remaining.len())
+                .filter(|&i| remaining.is_char_boundary(i))
+                .any(|i| glob_match(rest, &remaining[i..]))
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fs, path::Path};
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chacha20poly1305::{aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng}, ChaCha20Poly1305, Nonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const KEY_LEN: usize = 32;
+
+/// On-disk format of an encrypted group (`<group>.enc`).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Envelope {
+    pub(crate) version: u32,
+    /// Argon2 salt, base64
+    pub(crate) salt: String,
+    /// Whether a key file was mixed into the key
+    #[serde(default)]
+    pub(crate) key_file: bool,
+    pub(crate) nonce: String,
+    pub(crate) ciphertext: String,
+}
+
+impl Envelope {
+    pub fn read(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_yaml::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+pub fn random_salt() -> String {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    STANDARD.encode(salt)
+}
+
+// Key file contents are hashed and appended, so both are needed to decrypt
+pub fn derive_key(passphrase: &str, key_file: Option<&Path>, salt: &str) -> Result<[u8; KEY_LEN], Box<dyn std::error::Error>> {
+    let mut material = passphrase.as_bytes().to_vec();
+    if let Some(path) = key_file {
+        material.extend_from_slice(&Sha256::digest(fs::read(path)?));
+    }
+    let salt = STANDARD.decode(salt)?;
+    let mut key = [0u8; KEY_LEN];
+    Argon2::default()
+        .hash_password_into(&material, &salt, &mut key)
+        .map_err(|e| format!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+pub fn seal(plaintext: &str, key: &[u8; KEY_LEN], salt: &str, key_file: bool) -> Result<Envelope, Box<dyn std::error::Error>> {
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| "Encryption failed")?;
+    Ok(Envelope {
+        version: 1,
+        salt: salt.to_string(),
+        key_file,
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+pub fn open(envelope: &Envelope, key: &[u8; KEY_LEN]) -> Result<String, Box<dyn std::error::Error>> {
+    let cipher = ChaCha20Poly1305::new(key.into());
+    let nonce = STANDARD.decode(&envelope.nonce)?;
+    if nonce.len() != 12 {
+        return Err("Corrupt encrypted group: bad nonce".into());
+    }
+    let ciphertext = STANDARD.decode(&envelope.ciphertext)?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Wrong passphrase or key file")?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+pub fn encode_key(key: &[u8; KEY_LEN]) -> String {
+    STANDARD.encode(key)
+}
+
+pub fn decode_key(encoded: &str) -> Option<[u8; KEY_LEN]> {
+    STANDARD.decode(encoded).ok()?.try_into().ok()
+}
+
+/// Short SHA-256 fingerprint of a public key, for comparing it with the publisher out of band.
+pub fn fingerprint(public_key: &str) -> String {
+    let digest = Sha256::digest(public_key.trim().as_bytes());
+    digest[..8].iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<_>>().join(":")
+}
+
+pub fn generate_signing_key() -> SigningKey {
+    SigningKey::generate(&mut OsRng)
+}
+
+pub fn read_signing_key(path: &Path) -> Result<SigningKey, Box<dyn std::error::Error>> {
+    let bytes: [u8; SECRET_KEY_LENGTH] = STANDARD
+        .decode(fs::read_to_string(path)?.trim())?
+        .try_into()
+        .map_err(|_| format!("{} is not an Ed25519 secret key", path.display()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+pub fn sign(message: &[u8], key: &SigningKey) -> String {
+    STANDARD.encode(key.sign(message).to_bytes())
+}
+
+pub fn verify_signature(message: &[u8], signature: &str, public_key: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let public_key: [u8; PUBLIC_KEY_LENGTH] = STANDARD
+        .decode(public_key)?
+        .try_into()
+        .map_err(|_| "Malformed public key")?;
+    let signature = Signature::from_slice(&STANDARD.decode(signature)?)?;
+    VerifyingKey::from_bytes(&public_key)?
+        .verify(message, &signature)
+        .map_err(|_| "Signature does not match".into())
+}
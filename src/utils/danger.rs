@@ -0,0 +1,117 @@
+use std::{path::PathBuf, process::Command};
+
+use regex::Regex;
+
+use crate::commands::{core::definition::{DangerRule, FolderConfig, SavedCommand}, trust::definition::TrustStore};
+
+// Built-in rules: id, pattern, message, kubectl context
+const DEFAULT_RULES: &[(&str, &str, &str, Option<&str>)] = &[
+    ("rm-root", r"\brm\s+(-\S*\s+)*-\S*[rR]\S*\s+(\S+\s+)*(/|/\*|~/?|\$HOME/?)(\s|;|$)", "recursive delete of / or the home directory", None),
+    ("dd-device", r"\bdd\b.*\bof=/dev/", "dd writing directly to a device", None),
+    ("mkfs", r"\bmkfs(\.\w+)?\b", "formats a filesystem", None),
+    ("fork-bomb", r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:", "fork bomb", None),
+    ("git-force-push", r"\bgit\s+push\b.*\s(--force|-f)(\s|$)", "force push rewrites remote history", None),
+    ("git-reset-hard", r"\bgit\s+reset\s+.*--hard\b", "discards uncommitted changes", None),
+    ("kubectl-delete-prod", r"\bkubectl\b.*\bdelete\b", "kubectl delete in a production context", Some("(?i)prod")),
+    ("sql-drop", r"(?i)\b(drop\s+(table|database|schema)|truncate\s+table)\b", "destructive SQL statement", None),
+    ("pipe-to-shell", r"\b(curl|wget)\b.*\|\s*(sudo\s+)?(ba|z)?sh\b", "pipes a download straight into a shell", None),
+];
+
+#[derive(Debug, Clone)]
+pub struct DangerMatch {
+    pub id: String,
+    pub message: String,
+}
+
+pub struct DangerChecker {
+    rules: Vec<(DangerRule, Regex)>,
+}
+
+impl DangerChecker {
+    /// Built-in rules adjusted by the `.config.yaml` of every folder in `dirs`.
+    pub fn for_dirs(dirs: &[PathBuf]) -> Self {
+        let configs: Vec<FolderConfig> = dirs.iter().map(|dir| FolderConfig::load(dir)).collect();
+
+        // Untrusted folders may add rules but not switch built-in ones off
+        let trust = TrustStore::load();
+        let disabled: Vec<&String> = dirs
+            .iter()
+            .zip(&configs)
+            .filter(|(dir, _)| trust.is_trusted(dir))
+            .flat_map(|(_, c)| &c.danger.disable)
+            .collect();
+
+        let builtin = DEFAULT_RULES.iter().map(|(id, pattern, message, kube_context)| DangerRule {
+            id: id.to_string(),
+            pattern: pattern.to_string(),
+            message: message.to_string(),
+            kube_context: kube_context.map(str::to_string),
+        });
+        let custom = configs.iter().flat_map(|c| c.danger.rules.iter().cloned());
+
+        let rules = builtin
+            .filter(|rule| !disabled.contains(&&rule.id))
+            .chain(custom)
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((rule, regex)),
+                Err(e) => {
+                    eprintln!("Warning: ignoring danger rule '{}': {}", rule.id, e);
+                    None
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    pub fn check(&self, command: &SavedCommand) -> Vec<DangerMatch> {
+        let mut matches = Vec::new();
+        if command.danger {
+            matches.push(DangerMatch {
+                id: "marked".to_string(),
+                message: "marked as dangerous".to_string(),
+            });
+        }
+
+        for (rule, regex) in &self.rules {
+            if !regex.is_match(&command.command) {
+                continue;
+            }
+            if let Some(ref context_pattern) = rule.kube_context {
+                if !kube_context_matches(&command.command, context_pattern) {
+                    continue;
+                }
+            }
+            matches.push(DangerMatch {
+                id: rule.id.clone(),
+                message: rule.message.clone(),
+            });
+        }
+        matches
+    }
+}
+
+// An explicit --context wins, then the current kubectl context. When neither
+// can be determined the rule applies, erring on the side of asking.
+fn kube_context_matches(command: &str, pattern: &str) -> bool {
+    let Ok(regex) = Regex::new(pattern) else {
+        return true;
+    };
+
+    let explicit = Regex::new(r"--context[=\s]+(\S+)")
+        .ok()
+        .and_then(|re| re.captures(command).map(|c| c[1].to_string()));
+    let context = explicit.or_else(|| {
+        Command::new("kubectl")
+            .args(["config", "current-context"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    });
+
+    match context {
+        Some(context) => regex.is_match(&context) || regex.is_match(command),
+        None => true,
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, path::PathBuf};
+use std::{env, fs::{self, File, OpenOptions}, io::{self, Write}, path::{Path, PathBuf}};
 
 pub fn find_sebas_dir() -> Option<PathBuf> {
         let mut current_dir = env::current_dir().ok()?;
@@ -29,3 +29,72 @@ pub fn get_all_sebas_dirs() -> Vec<PathBuf> {
         }
         dirs
     }
+
+pub fn get_state_dir() -> Option<PathBuf> {
+        if let Some(state_home) = env::var_os("XDG_STATE_HOME").filter(|v| !v.is_empty()) {
+            return Some(PathBuf::from(state_home).join("sebas"));
+        }
+        home::home_dir().map(|home| home.join(".local").join("state").join("sebas"))
+    }
+
+// Hidden files such as `.config.yaml` hold settings, not commands
+pub fn is_group_file(path: &Path) -> bool {
+        path.extension().and_then(|s| s.to_str()) == Some("yaml")
+            && !path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'))
+    }
+
+// Short-lived files such as the unlock session prefer the tmpfs runtime dir
+pub fn get_runtime_dir() -> Option<PathBuf> {
+        match env::var_os("XDG_RUNTIME_DIR").filter(|v| !v.is_empty()) {
+            Some(runtime) => Some(PathBuf::from(runtime).join("sebas")),
+            None => get_state_dir(),
+        }
+    }
+
+// Group names become file names in `.sebas`, so nothing that leaves the folder or hides the file
+pub fn is_valid_group_name(name: &str) -> bool {
+        !name.trim().is_empty()
+            && !name.contains(['/', '\\'])
+            && !name.contains("..")
+            && !name.starts_with('.')
+    }
+
+// Per-user files can hold secrets, so they are private from the moment they exist
+pub fn create_private_dir(path: &Path) -> io::Result<()> {
+        let mut builder = fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder.create(path)
+    }
+
+/// Create a new file only the user can read, failing if `path` already exists.
+pub fn create_private_file(path: &Path) -> io::Result<File> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options.open(path)
+    }
+
+/// Replace `path` with `contents` as a private file. The content goes to a temporary
+/// file first, so an interrupted write never leaves half a file behind.
+pub fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        create_private_dir(parent)?;
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("state");
+        let temp = parent.join(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4().simple()));
+        let written = create_private_file(&temp)
+            .and_then(|mut file| file.write_all(contents.as_bytes()).and_then(|_| file.sync_all()))
+            .and_then(|_| fs::rename(&temp, path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        written
+    }
@@ -3,7 +3,9 @@ use std::io::Write;
 use std::process::{Command as CliCommand, Stdio};
 use crate::commands::core::definition::ResolvedCommand;
 
-pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>) -> Result<ResolvedCommand, Error> {
+/// Let the user choose among `commands`. Keys in `expect` accept the selection like
+/// enter does and are returned alongside it.
+pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>, profile: Option<&str>, expect: &[&str]) -> Result<(ResolvedCommand, Option<String>), Error> {
     // Create a vector of display strings for fzf
     let mut display_items = Vec::new();
     // Create a vector to store comments and paths for preview
@@ -12,10 +14,12 @@ pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>) -> Result<ResolvedCommand,
     // Build the display list with group and command, and prepare preview data
     for (index, cmd) in commands.iter().enumerate() {
         // Create the display string without comment and path
+        let marker = if cmd.trusted { "" } else { " [untrusted]" };
         let display = format!(
-            "[{}] {} | {}", 
-            format!("{:1}", index + 1), 
+            "[{:1}] {}{} | {}", 
+            index + 1, 
             cmd.group, 
+            marker,
             cmd.command.command
         );
         display_items.push(display);
@@ -41,11 +45,18 @@ pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>) -> Result<ResolvedCommand,
     }
 
     // Step 1: Launch fzf with enhanced preview and styling
+    // The environment commands will run against, in plain sight
+    let profile = profile.map(|name| format!(" [profile: {}]", name)).unwrap_or_default();
     let mut fzf = CliCommand::new("fzf")
         .arg("--height=60%")
         .arg("--border=rounded")
         .arg("--prompt=SEBAS Commands › ")
-        .arg("--header=Select a command to retrieve")
+        .arg(if expect.is_empty() {
+            format!("--header=Select a command to retrieve{}", profile)
+        } else {
+            format!("--header=Select a command to retrieve, {} copies it{}", expect.join(" or "), profile)
+        })
+        .args((!expect.is_empty()).then(|| format!("--expect={}", expect.join(","))))
         .arg("--header-lines=0")
         .arg("--info=inline")
         .arg("--layout=reverse")
@@ -82,10 +93,14 @@ pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>) -> Result<ResolvedCommand,
         return Err(anyhow!("No command selected or fzf was cancelled"));
     }
 
-    let selected_display = String::from_utf8(fzf_output.stdout)
-        .context("Failed to parse fzf output")?
-        .trim()
-        .to_string();
+    let output = String::from_utf8(fzf_output.stdout).context("Failed to parse fzf output")?;
+    // With --expect the first line holds the key, empty for enter
+    let (key, selected_display) = if expect.is_empty() {
+        (None, output.trim().to_string())
+    } else {
+        let (key, rest) = output.split_once('\n').unwrap_or((&output, ""));
+        (Some(key.to_string()).filter(|key| !key.is_empty()), rest.trim().to_string())
+    };
 
     // Step 4: Extract just the command from the selected display string
     // The format is "[index] group | Command"
@@ -103,6 +118,6 @@ pub fn sebas_fzf_run(commands: Vec<ResolvedCommand>) -> Result<ResolvedCommand,
         .ok_or_else(|| anyhow!("Selected command not found in original list"))?;
 
     // Step 6: Execute only the actual command (not the display string)
-    Ok(resolved_command.to_owned())
+    Ok((resolved_command.to_owned(), key))
 
 }
@@ -0,0 +1,78 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use regex::Regex;
+
+// One path component, `*` and `?` never cross a slash
+fn component(part: &str) -> Result<Regex, regex::Error> {
+    let mut pattern = String::from("^");
+    for c in part.chars() {
+        match c {
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern)
+}
+
+// Hidden directories only match parts that ask for them, like `.github`.
+// Symlinks are not followed, a link back up the tree would make `**` endless
+fn subdirs(dir: &Path, include_hidden: bool) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| include_hidden || !path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.')))
+        .collect()
+}
+
+fn descendants(dir: &Path, found: &mut Vec<PathBuf>) {
+    found.push(dir.to_path_buf());
+    for sub in subdirs(dir, false) {
+        descendants(&sub, found);
+    }
+}
+
+/// Directories matching `pattern`, which may use `*`, `?` and `**` for any depth.
+/// Relative patterns start at `base`.
+pub fn matching_dirs(base: &Path, pattern: &str) -> Result<Vec<PathBuf>, regex::Error> {
+    let (mut dirs, rest) = match pattern.strip_prefix('/') {
+        Some(rest) => (vec![PathBuf::from("/")], rest),
+        None => (vec![base.to_path_buf()], pattern),
+    };
+
+    for part in rest.split('/').filter(|part| !part.is_empty() && *part != ".") {
+        dirs = if part == "**" {
+            let mut found = Vec::new();
+            for dir in &dirs {
+                descendants(dir, &mut found);
+            }
+            found
+        } else if !part.contains(['*', '?']) {
+            dirs.into_iter().map(|dir| dir.join(part)).filter(|dir| dir.is_dir()).collect()
+        } else {
+            let regex = component(part)?;
+            dirs.iter()
+                .flat_map(|dir| subdirs(dir, part.starts_with('.')))
+                .filter(|dir| dir.file_name().and_then(|n| n.to_str()).is_some_and(|n| regex.is_match(n)))
+                .collect()
+        };
+    }
+
+    let mut dirs: Vec<PathBuf> = dirs
+        .into_iter()
+        .map(|dir| match dir.strip_prefix("./") {
+            // `**` matches the base itself, which would strip to nothing
+            Ok(rest) if rest.as_os_str().is_empty() => dir,
+            Ok(rest) => rest.to_path_buf(),
+            Err(_) => dir,
+        })
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    Ok(dirs)
+}
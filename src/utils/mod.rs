@@ -1,4 +1,15 @@
+pub mod cheatsheets;
+pub mod clipboard;
+pub mod context;
+pub mod crypto;
+pub mod danger;
 pub mod dir;
 pub mod fzf;
+pub mod glob;
+pub mod output;
+pub mod placeholders;
 pub mod preseed;
+pub mod secrets;
+pub mod shell;
+pub mod tasks;
 
@@ -0,0 +1,84 @@
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// A JSON array
+    Json,
+    /// One JSON object per line
+    Jsonl,
+    Yaml,
+    /// Tab separated values with a header row
+    Tsv,
+    /// Aligned columns for reading
+    Table,
+}
+
+// Scalars as they are, nested values as compact JSON, all on one line
+fn cell(value: Option<&Value>, escape_tabs: bool) -> String {
+    let text = match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    if escape_tabs {
+        text.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+    } else {
+        text.replace('\n', " ")
+    }
+}
+
+/// Print `items` in `format`. `columns` selects the fields of the tabular formats.
+pub fn render<T: Serialize>(items: &[T], format: OutputFormat, columns: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    // A reader like `head` closing the pipe early is not an error
+    match write_items(&mut io::stdout().lock(), items, format, columns) {
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => Ok(()),
+        result => result,
+    }
+}
+
+fn write_items<T: Serialize>(out: &mut impl Write, items: &[T], format: OutputFormat, columns: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(items)?)?,
+        OutputFormat::Jsonl => {
+            for item in items {
+                writeln!(out, "{}", serde_json::to_string(item)?)?;
+            }
+        }
+        OutputFormat::Yaml => write!(out, "{}", serde_yaml::to_string(items)?)?,
+        OutputFormat::Tsv | OutputFormat::Table => {
+            let escape_tabs = format == OutputFormat::Tsv;
+            let mut rows = vec![columns.iter().map(|c| c.to_string()).collect::<Vec<_>>()];
+            for item in items {
+                let value = serde_json::to_value(item)?;
+                rows.push(columns.iter().map(|c| cell(value.get(c), escape_tabs)).collect());
+            }
+
+            if escape_tabs {
+                for row in rows {
+                    writeln!(out, "{}", row.join("\t"))?;
+                }
+                return Ok(());
+            }
+
+            let widths: Vec<usize> = (0..columns.len())
+                .map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0))
+                .collect();
+            for (n, row) in rows.iter().enumerate() {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(text, width)| {
+                        let text = if n == 0 { text.to_uppercase() } else { text.clone() };
+                        format!("{:width$}", text, width = width)
+                    })
+                    .collect();
+                writeln!(out, "{}", line.join("  ").trim_end())?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use regex::Regex;
+
+// `<name>` with no space right inside the brackets, so `a < b > c` is left alone
+fn placeholder() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    CELL.get_or_init(|| Regex::new(r"<([^<>\s](?:[^<>\n]*[^<>\s])?)>").unwrap())
+}
+
+/// Names of the `<name>` placeholders of `command`, each once, in order.
+pub fn placeholders(command: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for capture in placeholder().captures_iter(command) {
+        let name = capture[1].to_string();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Replace every placeholder that has a value, others stay as they are.
+pub fn expand(command: &str, values: &BTreeMap<String, String>) -> String {
+    placeholder()
+        .replace_all(command, |capture: &regex::Captures| {
+            values.get(&capture[1]).cloned().unwrap_or_else(|| capture[0].to_string())
+        })
+        .into_owned()
+}
+
+fn variable() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    CELL.get_or_init(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").unwrap())
+}
+
+/// Replace `${var}` references defined in `vars`, others are left for the shell.
+pub fn substitute(text: &str, vars: &BTreeMap<String, String>) -> String {
+    variable()
+        .replace_all(text, |capture: &regex::Captures| {
+            vars.get(&capture[1]).cloned().unwrap_or_else(|| capture[0].to_string())
+        })
+        .into_owned()
+}
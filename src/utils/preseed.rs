@@ -15,9 +15,13 @@ pub fn preseed(resolved_command: ResolvedCommand) -> Result<(), std::io::Error>
 
 
 
+    // Passed as an argument so quotes in the command survive
+    let line = resolved_command.shell_line(&resolved_command.command.command).map_err(std::io::Error::other)?;
     Command::new("bash")
         .arg("-c")
-        .arg(format!("echo '{}' | perl -e 'ioctl STDOUT, 0x5412, $_ for split //, do{{ chomp($_ = <>); $_ }}'", resolved_command.command.command))
+        .arg("printf '%s\\n' \"$1\" | perl -e 'ioctl STDOUT, 0x5412, $_ for split //, do{ chomp($_ = <>); $_ }'")
+        .arg("bash")
+        .arg(line)
         .stdin(Stdio::inherit())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
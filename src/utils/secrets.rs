@@ -0,0 +1,157 @@
+use std::{collections::HashMap, sync::OnceLock};
+
+use regex::Regex;
+
+#[derive(Debug, Clone)]
+pub struct SecretFinding {
+    pub start: usize,
+    pub end: usize,
+    pub kind: &'static str,
+    /// Environment variable suggested as replacement
+    pub env_name: String,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Redaction {
+    Env,
+    Placeholder,
+}
+
+// Token formats with a recognisable prefix: kind, pattern, env name
+const KNOWN_TOKENS: &[(&str, &str, &str)] = &[
+    ("GitHub token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b", "GITHUB_TOKEN"),
+    ("GitHub token", r"\bgithub_pat_[A-Za-z0-9_]{22,}\b", "GITHUB_TOKEN"),
+    ("GitLab token", r"\bglpat-[A-Za-z0-9_\-]{20,}", "GITLAB_TOKEN"),
+    ("Slack token", r"\bxox[baprs]-[A-Za-z0-9\-]{10,}", "SLACK_TOKEN"),
+    ("AWS access key", r"\bAKIA[0-9A-Z]{16}\b", "AWS_ACCESS_KEY_ID"),
+    ("Stripe key", r"\b[sr]k_live_[A-Za-z0-9]{16,}\b", "STRIPE_API_KEY"),
+    ("API key", r"\bsk-[A-Za-z0-9_\-]{20,}", "API_KEY"),
+    ("Google API key", r"\bAIza[0-9A-Za-z_\-]{35}", "GOOGLE_API_KEY"),
+    ("JWT", r"\beyJ[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,}\.[A-Za-z0-9_\-]{10,}", "JWT"),
+];
+
+// Secrets recognised by what surrounds them, the last capture group is the secret
+const CONTEXTUAL: &[(&str, &str, &str)] = &[
+    ("authorization header", r"(?i)authorization:\s*(?:bearer|token|basic)\s+([^\s'\x22]+)", "API_TOKEN"),
+    ("password flag", r"(?i)--(?:password|passwd|token|api-key|secret)[= ]+([^\s'\x22$]+)", "PASSWORD"),
+    ("MySQL password", r"\bmysql(?:dump|admin)?\b.*\s-p([^\s'\x22$]+)", "MYSQL_PWD"),
+    ("sshpass password", r"\bsshpass\s+-p\s*([^\s'\x22$]+)", "SSHPASS"),
+    ("URL credentials", r"://[^/\s:@]+:([^/\s@$]+)@", "PASSWORD"),
+    ("basic auth", r"(?:\s-u|--user)\s+[^\s:]+:([^\s'\x22$]+)", "PASSWORD"),
+];
+
+fn regexes(patterns: &'static [(&'static str, &'static str, &'static str)]) -> Vec<(&'static str, Regex, &'static str)> {
+    patterns
+        .iter()
+        .filter_map(|(kind, pattern, env)| Regex::new(pattern).ok().map(|re| (*kind, re, *env)))
+        .collect()
+}
+
+fn known_tokens() -> &'static Vec<(&'static str, Regex, &'static str)> {
+    static CELL: OnceLock<Vec<(&str, Regex, &str)>> = OnceLock::new();
+    CELL.get_or_init(|| regexes(KNOWN_TOKENS))
+}
+
+fn contextual() -> &'static Vec<(&'static str, Regex, &'static str)> {
+    static CELL: OnceLock<Vec<(&str, Regex, &str)>> = OnceLock::new();
+    CELL.get_or_init(|| regexes(CONTEXTUAL))
+}
+
+fn env_assignment() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    CELL.get_or_init(|| {
+        Regex::new(r"\b([A-Z0-9_]*(?:SECRET|TOKEN|PASSWORD|PASSWD|API_KEY|PRIVATE_KEY|ACCESS_KEY)[A-Z0-9_]*)=([^\s'\x22$][^\s'\x22]*)").unwrap()
+    })
+}
+
+fn candidate_words() -> &'static Regex {
+    static CELL: OnceLock<Regex> = OnceLock::new();
+    CELL.get_or_init(|| Regex::new(r"[A-Za-z0-9+/_\-=]{20,}").unwrap())
+}
+
+// Shannon entropy in bits per character
+fn entropy(value: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+pub fn scan(command: &str) -> Vec<SecretFinding> {
+    let mut findings: Vec<SecretFinding> = Vec::new();
+    let mut push = |finding: SecretFinding| {
+        if !findings.iter().any(|f| finding.start < f.end && f.start < finding.end) {
+            findings.push(finding);
+        }
+    };
+
+    for (kind, regex, env_name) in known_tokens() {
+        for m in regex.find_iter(command) {
+            push(SecretFinding { start: m.start(), end: m.end(), kind, env_name: env_name.to_string() });
+        }
+    }
+    for (kind, regex, env_name) in contextual() {
+        for caps in regex.captures_iter(command) {
+            if let Some(m) = caps.iter().flatten().last() {
+                push(SecretFinding { start: m.start(), end: m.end(), kind, env_name: env_name.to_string() });
+            }
+        }
+    }
+    for caps in env_assignment().captures_iter(command) {
+        if let (Some(name), Some(value)) = (caps.get(1), caps.get(2)) {
+            push(SecretFinding { start: value.start(), end: value.end(), kind: "secret variable", env_name: name.as_str().to_string() });
+        }
+    }
+    for m in candidate_words().find_iter(command) {
+        let word = m.as_str();
+        let mixed = word.chars().any(|c| c.is_ascii_digit()) && word.chars().any(|c| c.is_ascii_alphabetic());
+        if mixed && !word.starts_with(['/', '.']) && !word.contains("//") && entropy(word) > 4.2 {
+            push(SecretFinding { start: m.start(), end: m.end(), kind: "high-entropy string", env_name: "SECRET".to_string() });
+        }
+    }
+
+    findings.sort_by_key(|f| f.start);
+    findings
+}
+
+// Single-quoted text does not expand variables, so splice the reference out of the quotes
+fn in_single_quotes(command: &str, position: usize) -> bool {
+    let mut quoted = false;
+    let mut double = false;
+    let mut escaped = false;
+    for c in command[..position].chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if !quoted => escaped = true,
+            '\'' if !double => quoted = !quoted,
+            '"' if !quoted => double = !double,
+            _ => {}
+        }
+    }
+    quoted
+}
+
+pub fn replacement(command: &str, finding: &SecretFinding, redaction: Redaction) -> String {
+    match redaction {
+        Redaction::Placeholder => format!("<{}>", finding.env_name.to_lowercase()),
+        Redaction::Env if in_single_quotes(command, finding.start) => format!("'\"${{{}}}\"'", finding.env_name),
+        Redaction::Env => format!("${{{}}}", finding.env_name),
+    }
+}
+
+/// Show enough of a secret to recognise it without echoing it in full.
+pub fn mask(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    format!("{}…{}", chars[..4].iter().collect::<String>(), chars[chars.len() - 2..].iter().collect::<String>())
+}
@@ -0,0 +1,65 @@
+use std::env;
+
+// Quote a string for POSIX shells, leaving simple words untouched
+pub fn quote(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c));
+    if is_plain {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+pub fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Names a POSIX shell accepts in a `NAME=value` prefix
+pub fn is_valid_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Shells that understand `cd dir && NAME=value command`
+pub fn is_posix_shell(shell: &str) -> bool {
+    matches!(shell, "bash" | "zsh" | "sh" | "dash")
+}
+
+/// Shells a command variant can be written for, all of them accept `-c`.
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish", "nu", "sh", "dash", "pwsh", "elvish"];
+
+// `-zsh` for login shells, full paths from $SHELL, `pwsh.exe` on Windows
+fn shell_name(program: &str) -> Option<String> {
+    let name = program.trim().rsplit(['/', '\\']).next()?.trim_start_matches('-');
+    let name = name.strip_suffix(".exe").unwrap_or(name);
+    SHELLS.contains(&name).then(|| name.to_string())
+}
+
+#[cfg(unix)]
+fn parent_shell() -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "comm=", "-p", &std::os::unix::process::parent_id().to_string()])
+        .output()
+        .ok()?;
+    shell_name(&String::from_utf8_lossy(&output.stdout))
+}
+
+// There is no portable way to name the parent process, only PowerShell leaves a trace
+#[cfg(not(unix))]
+fn parent_shell() -> Option<String> {
+    env::var_os("PSModulePath")
+        .filter(|paths| paths.to_string_lossy().contains("PowerShell\\7"))
+        .map(|_| "pwsh".to_string())
+}
+
+/// The shell sebas was started from: `SEBAS_SHELL`, then the parent process, then `$SHELL`.
+pub fn current_shell() -> Option<String> {
+    if let Some(shell) = env::var("SEBAS_SHELL").ok().filter(|shell| !shell.is_empty()) {
+        return Some(shell);
+    }
+    parent_shell().or_else(|| env::var("SHELL").ok().and_then(|shell| shell_name(&shell)))
+}
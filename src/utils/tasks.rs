@@ -0,0 +1,215 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::commands::import::definition::ImportedCommand;
+
+// Discover every supported task source in `dir`
+pub fn discover_tasks(dir: &Path) -> Vec<ImportedCommand> {
+    let mut tasks = Vec::new();
+
+    for name in ["Makefile", "makefile", "GNUmakefile"] {
+        if let Ok(content) = fs::read_to_string(dir.join(name)) {
+            tasks.extend(parse_makefile(&content));
+            break;
+        }
+    }
+    for name in ["justfile", "Justfile", ".justfile"] {
+        if let Ok(content) = fs::read_to_string(dir.join(name)) {
+            tasks.extend(parse_justfile(&content));
+            break;
+        }
+    }
+    if let Ok(content) = fs::read_to_string(dir.join("package.json")) {
+        tasks.extend(parse_package_json(&content, npm_runner(dir)));
+    }
+    for name in ["config.toml", "config"] {
+        if let Ok(content) = fs::read_to_string(dir.join(".cargo").join(name)) {
+            tasks.extend(parse_cargo_aliases(&content));
+            break;
+        }
+    }
+
+    tasks
+}
+
+fn npm_runner(dir: &Path) -> &'static str {
+    if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm run"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn run"
+    } else if dir.join("bun.lockb").exists() || dir.join("bun.lock").exists() {
+        "bun run"
+    } else {
+        "npm run"
+    }
+}
+
+// Comment from a `target: deps ## description` line, a preceding `#` line or the recipe
+fn describe(inline: Option<&str>, doc: Option<String>, recipe: &[String]) -> Option<String> {
+    inline
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .or(doc)
+        .or_else(|| (!recipe.is_empty()).then(|| recipe.join("; ")))
+}
+
+pub fn parse_makefile(content: &str) -> Vec<ImportedCommand> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tasks = Vec::new();
+    let mut doc: Option<String> = None;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if let Some(comment) = line.strip_prefix('#') {
+            doc = Some(comment.trim_start_matches('#').trim().to_string()).filter(|c| !c.is_empty());
+            continue;
+        }
+        if line.starts_with(['\t', ' ', '.']) || line.trim().is_empty() {
+            doc = None;
+            continue;
+        }
+
+        let Some((targets, rest)) = line.split_once(':') else {
+            doc = None;
+            continue;
+        };
+        if rest.starts_with('=') || targets.contains(['=', '$', '%']) {
+            doc = None;
+            continue;
+        }
+
+        let inline = rest.split_once("##").map(|(_, d)| d);
+        let mut recipe = Vec::new();
+        while i < lines.len() && lines[i].starts_with('\t') {
+            recipe.push(lines[i].trim().trim_start_matches(['@', '-']).to_string());
+            i += 1;
+        }
+
+        let comment = describe(inline, doc.take(), &recipe);
+        for target in targets.split_whitespace() {
+            tasks.push(ImportedCommand {
+                command: format!("make {}", target),
+                comment: comment.clone(),
+                group: None,
+                vars: BTreeMap::new(),
+                cwd: None,
+            });
+        }
+    }
+
+    tasks
+}
+
+pub fn parse_justfile(content: &str) -> Vec<ImportedCommand> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut tasks = Vec::new();
+    let mut doc: Option<String> = None;
+    let mut private = false;
+
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+
+        if let Some(comment) = line.strip_prefix('#') {
+            doc = Some(comment.trim().to_string()).filter(|c| !c.is_empty() && !c.starts_with('!'));
+            continue;
+        }
+        if line.starts_with('[') {
+            private |= line.contains("private");
+            continue;
+        }
+        if line.starts_with([' ', '\t']) || line.trim().is_empty() {
+            doc = None;
+            private = false;
+            continue;
+        }
+
+        let header = line;
+        let keyword = header.split_whitespace().next().unwrap_or_default();
+        let is_recipe = match header.split_once(':') {
+            Some((_, rest)) => !rest.starts_with('='),
+            None => false,
+        };
+        if !is_recipe || ["set", "alias", "export", "import", "mod"].contains(&keyword) {
+            doc = None;
+            private = false;
+            continue;
+        }
+
+        let mut recipe = Vec::new();
+        while i < lines.len() && (lines[i].starts_with([' ', '\t']) || lines[i].trim().is_empty()) {
+            let step = lines[i].trim();
+            if !step.is_empty() {
+                recipe.push(step.trim_start_matches(['@', '-']).to_string());
+            }
+            i += 1;
+        }
+
+        let name = keyword.trim_start_matches('@').trim_end_matches(':');
+        if !private && !name.starts_with('_') && !name.is_empty() {
+            tasks.push(ImportedCommand {
+                command: format!("just {}", name),
+                comment: describe(None, doc.take(), &recipe),
+                group: None,
+                vars: BTreeMap::new(),
+                cwd: None,
+            });
+        }
+        doc = None;
+        private = false;
+    }
+
+    tasks
+}
+
+pub fn parse_package_json(content: &str, runner: &str) -> Vec<ImportedCommand> {
+    let Ok(package) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(scripts) = package.get("scripts").and_then(|s| s.as_object()) else {
+        return Vec::new();
+    };
+
+    scripts
+        .iter()
+        .map(|(name, script)| ImportedCommand {
+            command: format!("{} {}", runner, name),
+            comment: script.as_str().map(str::to_string),
+            group: None,
+            vars: BTreeMap::new(),
+            cwd: None,
+        })
+        .collect()
+}
+
+pub fn parse_cargo_aliases(content: &str) -> Vec<ImportedCommand> {
+    let Ok(config) = content.parse::<toml::Table>() else {
+        return Vec::new();
+    };
+    let Some(aliases) = config.get("alias").and_then(|a| a.as_table()) else {
+        return Vec::new();
+    };
+
+    aliases
+        .iter()
+        .map(|(name, expansion)| {
+            let comment = match expansion {
+                toml::Value::String(s) => Some(s.clone()),
+                toml::Value::Array(parts) => Some(
+                    parts.iter().filter_map(|p| p.as_str()).collect::<Vec<_>>().join(" "),
+                ),
+                _ => None,
+            };
+            ImportedCommand {
+                command: format!("cargo {}", name),
+                comment,
+                group: None,
+                vars: BTreeMap::new(),
+                cwd: None,
+            }
+        })
+        .collect()
+}